@@ -0,0 +1,34 @@
+//! writing a burst of 100k info lines, flushed after every line and buffered. the lines go to stdout and the timings to
+//! stderr, so run with `cargo bench --bench burst > /dev/null`
+
+use std::time::{Duration, Instant};
+
+use humantalk::Config;
+
+const LINES: usize = 100_000;
+
+/// time writing `LINES` info lines, then flushing them
+fn burst(buffered: bool) -> Duration {
+    let mut config = Config::default();
+    config.set_buffered(buffered);
+
+    let start = Instant::now();
+    for i in 0..LINES {
+        config.info(&format!("row {} of the report", i));
+    }
+    config.flush();
+
+    start.elapsed()
+}
+
+fn main() {
+    let unbuffered = burst(false);
+    let buffered = burst(true);
+
+    eprintln!("unbuffered: {:?} for {} lines", unbuffered, LINES);
+    eprintln!("buffered:   {:?} for {} lines", buffered, LINES);
+    eprintln!(
+        "buffered is {:.1}x faster",
+        unbuffered.as_secs_f64() / buffered.as_secs_f64()
+    );
+}
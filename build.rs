@@ -0,0 +1,25 @@
+//! records the version of the toolchain humantalk is compiled with, so `machine_info` doesn't have to run `rustc`
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    match rustc_version::version_meta() {
+        Ok(meta) => {
+            println!(
+                "cargo:rustc-env=HUMANTALK_RUSTC_VERSION={}",
+                meta.short_version_string
+            );
+
+            if let Some(llvm) = meta.llvm_version {
+                println!(
+                    "cargo:rustc-env=HUMANTALK_LLVM_VERSION={}.{}",
+                    llvm.major, llvm.minor
+                );
+            }
+        }
+        Err(e) => println!(
+            "cargo:warning=could not read the rustc version, it will be reported as unknown: {}",
+            e
+        ),
+    }
+}
@@ -0,0 +1,150 @@
+//! reporting `anyhow::Error`s, behind the `anyhow` feature
+
+use std::backtrace::BacktraceStatus;
+
+use crate::{source::error_causes, Config};
+
+impl Config {
+    /// write `error` as an error, followed by each layer of its context on its own `caused by:` line
+    ///
+    /// # Examples
+    /// ```rust
+    /// use anyhow::Context;
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// if let Err(e) = std::fs::read_to_string("settings.toml").context("loading settings") {
+    ///     config.report(&e);
+    /// }
+    /// ```
+    pub fn report(&self, error: &anyhow::Error) {
+        self.error_with_source(error.as_ref());
+    }
+
+    /// error fatally like `fatal_error_from`, listing `error`'s context chain under `[CAUSED BY]`. if anyhow captured
+    /// a backtrace where the error was created, it is used in the crash report instead of one captured here
+    pub fn fatal_from(&self, error: anyhow::Error) -> ! {
+        self.fatal_anyhow(&error)
+    }
+
+    /// same as `fatal_from`, for when only a reference to the error is at hand
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use anyhow::Context;
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// let result = std::fs::read_to_string("settings.toml").context("loading settings");
+    /// if let Err(e) = &result {
+    ///     config.fatal_anyhow(e);
+    /// }
+    /// ```
+    pub fn fatal_anyhow(&self, error: &anyhow::Error) -> ! {
+        let mut report = self.build_error_report(error.to_string(), error_causes(error.as_ref()));
+
+        if self.capture_backtrace && error.backtrace().status() == BacktraceStatus::Captured {
+            report.backtrace = Some(error.backtrace().to_string());
+        }
+
+        let code = self.report_crash(&report);
+        self.exit(code, &report.message)
+    }
+}
+
+/// run `main`, turning an `Err` it returns into a fatal error through `config`. intended for wrapping the body of
+/// `main` in programs that use anyhow
+///
+/// # Examples
+/// ```rust,no_run
+/// use anyhow::Context;
+/// use humantalk::Config;
+///
+/// fn main() {
+///     humantalk::run(&Config::default(), || {
+///         let settings = std::fs::read_to_string("settings.toml").context("loading settings")?;
+///         println!("{}", settings);
+///         Ok(())
+///     });
+/// }
+/// ```
+pub fn run(config: &Config, main: impl FnOnce() -> anyhow::Result<()>) {
+    if let Err(error) = main() {
+        config.fatal_from(error);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use anyhow::Context;
+
+    use super::*;
+    use crate::{ExitBehavior, Severity, CRASH_REPORT_FILE};
+
+    #[test]
+    fn test_report() {
+        let config = Config::default();
+        config.start_capture();
+
+        let error = anyhow::anyhow!("connection refused").context("request failed");
+        config.report(&error);
+
+        assert_eq!(
+            config.take_captured(),
+            vec![(
+                Severity::Error,
+                "request failed\n  caused by: connection refused".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_run() {
+        let dir = crate::test::scratch_dir("anyhow");
+        let mut config = Config::default();
+        config.set_crash_report_path(dir.join(CRASH_REPORT_FILE));
+        config.set_exit_behavior(ExitBehavior::Panic);
+
+        run(&config, || Ok(()));
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            run(&config, || {
+                "x".parse::<u8>().context("reading port")?;
+                Ok(())
+            })
+        }));
+
+        assert!(result.is_err());
+        assert!(std::fs::read_to_string(dir.join(CRASH_REPORT_FILE))
+            .unwrap()
+            .contains("[CAUSED BY]\n  invalid digit found in string\n"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_fatal_anyhow() {
+        let dir = crate::test::scratch_dir("fatal-anyhow");
+        let mut config = Config::default();
+        config.set_crash_report_path(dir.join(CRASH_REPORT_FILE));
+        config.set_capture_backtrace(false);
+        config.set_exit_behavior(ExitBehavior::Panic);
+
+        let error = anyhow::anyhow!("disk full")
+            .context("saving")
+            .context("shutting down");
+        let payload = catch_unwind(AssertUnwindSafe(|| config.fatal_anyhow(&error))).unwrap_err();
+        assert_eq!(
+            payload.downcast_ref::<String>().unwrap(),
+            "fatal error: shutting down"
+        );
+
+        let report = std::fs::read_to_string(dir.join(CRASH_REPORT_FILE)).unwrap();
+        assert!(report.contains("[CAUSED BY]\n  saving\n  disk full\n"));
+        assert!(!report.contains("[BACKTRACE]"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
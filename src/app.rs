@@ -0,0 +1,123 @@
+//! information about the application using humantalk, for crash reports
+
+use crate::{json_string, Config};
+
+/// the application's name and version, and optionally the commit and date it was built from. it is shown in the
+/// platform info of crash reports, and replaces `{app}` in the bug report message and url
+///
+/// # Examples
+/// ```rust
+/// use humantalk::{app_info, Config, HowToBugReport};
+/// let mut config = Config::default();
+///
+/// let mut app = app_info!(); // this crate's name and version
+/// app.git_commit = option_env!("GIT_COMMIT").map(str::to_string);
+/// config.set_app_info(app);
+///
+/// config.bug_report = Some(HowToBugReport::new(
+///     "{app} has crashed".to_string(),
+///     "https://github.com/werdl/humantalk/issues".to_string(),
+/// ));
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AppInfo {
+    /// the application's name, like `humantalk`
+    pub name: String,
+
+    /// the application's version, like `0.1.2`
+    pub version: String,
+
+    /// the commit the application was built from, if known
+    pub git_commit: Option<String>,
+
+    /// the date the application was built, if known
+    pub build_date: Option<String>,
+}
+
+impl AppInfo {
+    /// the info as a JSON object, for JSON crash reports
+    pub(crate) fn json(&self) -> String {
+        format!(
+            "{{\"name\":{},\"version\":{},\"git_commit\":{},\"build_date\":{}}}",
+            json_string(&self.name),
+            json_string(&self.version),
+            self.git_commit
+                .as_deref()
+                .map_or("null".to_string(), json_string),
+            self.build_date
+                .as_deref()
+                .map_or("null".to_string(), json_string)
+        )
+    }
+}
+
+/// `name version`, followed by the commit and build date if known, like `myapp 1.2.0 (commit 3f2a9c1, built 2024-05-02)`
+impl std::fmt::Display for AppInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {}", self.name, self.version)?;
+
+        let details: Vec<String> = [
+            self.git_commit
+                .as_ref()
+                .map(|commit| format!("commit {}", commit)),
+            self.build_date
+                .as_ref()
+                .map(|date| format!("built {}", date)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        if !details.is_empty() {
+            write!(f, " ({})", details.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Config {
+    /// set the application's info, shown in crash reports
+    pub fn set_app_info(&mut self, app_info: AppInfo) {
+        self.app_info = Some(app_info);
+    }
+
+    /// replace `{app}` in `text` with the application's name and version, if they have been set
+    pub(crate) fn fill_app(&self, text: &str) -> String {
+        match &self.app_info {
+            Some(app) => text.replace("{app}", &format!("{} {}", app.name, app.version)),
+            None => text.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_app_info() {
+        let mut app = crate::app_info!();
+        assert_eq!(app.name, "humantalk");
+        assert_eq!(app.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            app.to_string(),
+            format!("humantalk {}", env!("CARGO_PKG_VERSION"))
+        );
+
+        app.git_commit = Some("3f2a9c1".to_string());
+        app.build_date = Some("2024-05-02".to_string());
+        assert!(app
+            .to_string()
+            .ends_with(" (commit 3f2a9c1, built 2024-05-02)"));
+
+        let mut config = Config::default();
+        assert_eq!(config.fill_app("{app} crashed"), "{app} crashed");
+        config.set_app_info(app);
+        assert_eq!(
+            config.fill_app("{app} crashed"),
+            format!("humantalk {} crashed", env!("CARGO_PKG_VERSION"))
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! text attributes applied on top of each severity's color
+
+use console::StyledObject;
+
+use crate::{Config, Severity};
+
+/// bold, underline, dim and italic attributes for a severity's messages, set with `Config::set_style`. like colors,
+/// they are only written to sinks with color, so they disappear with `NO_COLOR`
+///
+/// # Examples
+/// ```rust
+/// use humantalk::{Config, Severity, StyleAttrs};
+/// let mut config = Config::default();
+///
+/// config.set_style(Severity::Warning, StyleAttrs { underline: true, ..StyleAttrs::default() });
+/// config.warning("underlined, as well as yellow");
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct StyleAttrs {
+    /// bold, or bright on some terminals
+    pub bold: bool,
+
+    /// underlined
+    pub underline: bool,
+
+    /// dimmed, or faint
+    pub dim: bool,
+
+    /// italic, which not every terminal shows
+    pub italic: bool,
+}
+
+impl StyleAttrs {
+    /// only bold
+    pub const BOLD: StyleAttrs = StyleAttrs {
+        bold: true,
+        underline: false,
+        dim: false,
+        italic: false,
+    };
+
+    /// add these attributes to `styled`
+    pub(crate) fn apply<D>(&self, mut styled: StyledObject<D>) -> StyledObject<D> {
+        if self.bold {
+            styled = styled.bold();
+        }
+        if self.underline {
+            styled = styled.underlined();
+        }
+        if self.dim {
+            styled = styled.dim();
+        }
+        if self.italic {
+            styled = styled.italic();
+        }
+
+        styled
+    }
+}
+
+impl Config {
+    /// find the text attributes for a given severity, none if they have not been set
+    pub fn get_style(&self, severity: &Severity) -> StyleAttrs {
+        self.styles.get(severity).copied().unwrap_or_default()
+    }
+
+    /// set the text attributes for the specified severity level. errors are bold by default
+    pub fn set_style(&mut self, severity: Severity, attrs: StyleAttrs) {
+        self.styles.insert(severity, attrs);
+    }
+}
+
+#[cfg(all(test, not(feature = "max_level_warn")))]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::{output::Output, test::SharedBuffer, Sink};
+
+    #[test]
+    fn test_style_attrs() {
+        let color = SharedBuffer::default();
+        let plain = SharedBuffer::default();
+
+        let mut config = Config::default();
+        config.output = Arc::new(Mutex::new(Output::new(Sink::new(color.clone(), true))));
+        config.add_sink(Sink::new(plain.clone(), false));
+        assert_eq!(config.get_style(&Severity::Error), StyleAttrs::BOLD);
+
+        config.set_style(
+            Severity::Info,
+            StyleAttrs {
+                underline: true,
+                italic: true,
+                ..StyleAttrs::default()
+            },
+        );
+        config.info("styled");
+        config.error("bold");
+
+        let raw = String::from_utf8(color.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = raw.lines().collect();
+        assert!(
+            lines[0].contains("\u{1b}[3m") && lines[0].contains("\u{1b}[4m"),
+            "{:?}",
+            lines[0]
+        );
+        assert!(!lines[0].contains("\u{1b}[1m"));
+        assert!(lines[1].contains("\u{1b}[1m"), "{:?}", lines[1]);
+
+        assert!(!String::from_utf8(plain.0.lock().unwrap().clone())
+            .unwrap()
+            .contains('\u{1b}'));
+    }
+}
@@ -0,0 +1,146 @@
+//! boxed headers printed when a program starts
+
+use console::{measure_text_width, pad_str, style, Alignment};
+
+use crate::{ColorToColor256, Config, MachineInfo, Severity};
+
+/// the characters a banner's box is drawn with
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum BoxStyle {
+    /// box-drawing characters, like `┌─┐`
+    #[default]
+    Unicode,
+
+    /// plain ASCII, like `+-+`, for terminals that can't show box-drawing characters
+    Ascii,
+}
+
+impl BoxStyle {
+    /// the top left, top right, bottom left and bottom right corners, then the horizontal and vertical edges
+    fn chars(&self) -> [&'static str; 6] {
+        match self {
+            BoxStyle::Unicode => ["┌", "┐", "└", "┘", "─", "│"],
+            BoxStyle::Ascii => ["+", "+", "+", "+", "-", "|"],
+        }
+    }
+}
+
+/// options for `Config::banner_with`
+///
+/// # Examples
+/// ```rust
+/// use humantalk::{BannerOptions, BoxStyle, Config};
+/// let config = Config::default();
+///
+/// config.banner_with(
+///     "myapp",
+///     "1.2.0",
+///     &BannerOptions {
+///         style: BoxStyle::Ascii,
+///         platform: true,
+///     },
+/// );
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct BannerOptions {
+    /// the characters the box is drawn with
+    pub style: BoxStyle,
+
+    /// whether to add a line with the platform, like `unix-linux-x86_64 (native)`, from `Config::machine_info_struct`
+    pub platform: bool,
+}
+
+impl Config {
+    /// print `name` and `version` in a box, in the info color, for the start of a program. nothing is printed if info
+    /// messages are suppressed
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// config.banner(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+    /// ```
+    pub fn banner(&self, name: &str, version: &str) {
+        self.banner_with(name, version, &BannerOptions::default());
+    }
+
+    /// print a banner like `banner`, drawn as set in `options`
+    pub fn banner_with(&self, name: &str, version: &str, options: &BannerOptions) {
+        if !self.should_write(&Severity::Info) {
+            return;
+        }
+
+        let mut lines = vec![format!("{} {}", name, version)];
+        if options.platform {
+            lines.push(platform(&self.machine_info_struct()));
+        }
+
+        let color = self.get_color(&Severity::Info).to_color256();
+        let indentation = self.indentation();
+        let mut output = self.lock_output();
+
+        for line in render_banner(&lines, options.style) {
+            let line = style(line).color256(color).bold().force_styling(true);
+            let _ = output.write_line(
+                &Severity::Info,
+                &format!("{}{}", indentation, line),
+                self.buffers(&Severity::Info),
+            );
+        }
+    }
+}
+
+/// a short summary of the platform, like `unix-linux-x86_64 (native)`
+fn platform(info: &MachineInfo) -> String {
+    format!(
+        "{}-{}-{} ({})",
+        info.family, info.os, info.arch, info.environment
+    )
+}
+
+/// `lines` in a box drawn with `box_style`, each padded to the widest
+fn render_banner(lines: &[String], box_style: BoxStyle) -> Vec<String> {
+    let [top_left, top_right, bottom_left, bottom_right, horizontal, vertical] = box_style.chars();
+    let width = lines
+        .iter()
+        .map(|line| measure_text_width(line))
+        .max()
+        .unwrap_or(0);
+    let edge = horizontal.repeat(width + 2);
+
+    std::iter::once(format!("{}{}{}", top_left, edge, top_right))
+        .chain(lines.iter().map(|line| {
+            format!(
+                "{0} {1} {0}",
+                vertical,
+                pad_str(line, width, Alignment::Left, None)
+            )
+        }))
+        .chain([format!("{}{}{}", bottom_left, edge, bottom_right)])
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_banner() {
+        let lines = vec!["myapp 1.2.0".to_string(), "unix-linux".to_string()];
+
+        assert_eq!(
+            render_banner(&lines, BoxStyle::Unicode),
+            vec![
+                "┌─────────────┐",
+                "│ myapp 1.2.0 │",
+                "│ unix-linux  │",
+                "└─────────────┘"
+            ]
+        );
+        assert_eq!(
+            render_banner(&lines[..1], BoxStyle::Ascii),
+            vec!["+-------------+", "| myapp 1.2.0 |", "+-------------+"]
+        );
+    }
+}
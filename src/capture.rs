@@ -0,0 +1,103 @@
+//! recording messages in memory instead of printing them, for tests
+
+use std::sync::PoisonError;
+
+use crate::{Config, Severity};
+
+/// messages recorded while capturing, or `None` when not capturing
+pub(crate) type Captured = Option<Vec<(Severity, String)>>;
+
+impl Config {
+    /// start recording messages instead of printing them. filtering still applies, so only messages that would have
+    /// been printed are recorded. clones of the config share the recording
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::{Config, Severity};
+    /// let config = Config::default();
+    ///
+    /// config.start_capture();
+    /// config.error("disk full");
+    /// assert_eq!(config.take_captured(), vec![(Severity::Error, "disk full".to_string())]);
+    /// ```
+    pub fn start_capture(&self) {
+        self.capture
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_or_insert_with(Vec::new);
+    }
+
+    /// remove and return the messages recorded so far, leaving capture on
+    pub fn take_captured(&self) -> Vec<(Severity, String)> {
+        self.capture
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// go back to printing messages, returning any that were recorded and not yet taken
+    pub fn stop_capture(&self) -> Vec<(Severity, String)> {
+        self.capture
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+            .unwrap_or_default()
+    }
+
+    /// record a message if capturing, returning whether it was recorded
+    pub(crate) fn try_capture(&self, severity: &Severity, message: &str) -> bool {
+        match self
+            .capture
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_mut()
+        {
+            Some(captured) => {
+                captured.push((severity.clone(), message.to_string()));
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "max_level_warn")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_capture() {
+        let mut config = Config::default();
+        config.set_min_severity(Severity::Info);
+        config.start_capture();
+
+        let threads: Vec<_> = (0..4)
+            .map(|i| {
+                let config = config.clone();
+                std::thread::spawn(move || config.info(&format!("thread {}", i)))
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        config.debug("filtered out");
+
+        let mut captured = config.take_captured();
+        captured.sort_by(|a, b| a.1.cmp(&b.1));
+        assert_eq!(
+            captured,
+            (0..4)
+                .map(|i| (Severity::Info, format!("thread {}", i)))
+                .collect::<Vec<_>>()
+        );
+
+        config.error("still capturing");
+        assert_eq!(
+            config.stop_capture(),
+            vec![(Severity::Error, "still capturing".to_string())]
+        );
+        assert!(config.take_captured().is_empty());
+    }
+}
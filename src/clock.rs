@@ -0,0 +1,27 @@
+//! the current time, from the browser on `wasm32-unknown-unknown` where std has no clock
+
+use thetime::{System, Time};
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) use std::time::Instant;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) use web_time::Instant;
+
+/// whether this is a browser-style wasm target, with no filesystem, processes or threads
+pub(crate) const WASM: bool = cfg!(all(target_arch = "wasm32", target_os = "unknown"));
+
+/// the current time of day
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) fn now() -> System {
+    System::now()
+}
+
+/// the current time of day, in UTC since the browser's time zone is not known
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) fn now() -> System {
+    let unix_ms = web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64);
+
+    System::from_epoch(unix_ms + thetime::OFFSET_1601 * 1000)
+}
@@ -0,0 +1,183 @@
+//! parsing colors from strings, for themes loaded from config files or the environment
+
+use console::Color;
+
+use crate::{Config, HumantalkError, Severity};
+
+/// levels of each channel in the 6x6x6 color cube of the 256-color palette
+pub(crate) const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// error returned by `parse_color` for a string that is not a color
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ColorParseError(String);
+
+impl std::fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "unknown color '{}', expected a color name, a number from 0 to 255 or #rrggbb",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ColorParseError {}
+
+/// parse a color, which is one of:
+/// - one of the eight named colors, such as `red` or `Cyan`
+/// - a 256-color code from `0` to `255`, as `Color::Color256`
+/// - a hex color such as `#ff8800`, as the closest `Color::Color256`
+///
+/// # Examples
+/// ```rust
+/// use humantalk::{parse_color, Color};
+///
+/// assert_eq!(parse_color("red").unwrap(), Color::Red);
+/// assert_eq!(parse_color("208").unwrap(), Color::Color256(208));
+/// assert_eq!(parse_color("#ff8700").unwrap(), Color::Color256(208));
+/// assert!(parse_color("reddish").is_err());
+/// ```
+pub fn parse_color(s: &str) -> Result<Color, HumantalkError> {
+    let trimmed = s.trim();
+    let error = || HumantalkError::ParseColor(ColorParseError(s.to_string()));
+
+    if trimmed.starts_with('#') {
+        return parse_hex(trimmed).ok_or_else(error);
+    }
+
+    if trimmed.starts_with(|c: char| c.is_ascii_digit()) {
+        return trimmed.parse().map(Color::Color256).map_err(|_| error());
+    }
+
+    match trimmed.to_ascii_lowercase().as_str() {
+        "black" => Ok(Color::Black),
+        "red" => Ok(Color::Red),
+        "green" => Ok(Color::Green),
+        "yellow" => Ok(Color::Yellow),
+        "blue" => Ok(Color::Blue),
+        "magenta" => Ok(Color::Magenta),
+        "cyan" => Ok(Color::Cyan),
+        "white" => Ok(Color::White),
+        _ => Err(error()),
+    }
+}
+
+/// parse `#rrggbb` as the closest `Color::Color256`
+fn parse_hex(s: &str) -> Option<Color> {
+    let hex = s.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::Color256(rgb_to_256(
+        channel(0)?,
+        channel(2)?,
+        channel(4)?,
+    )))
+}
+
+impl Config {
+    /// set the color for the specified severity level from a hex color like `#ff8800`, using the closest color of the
+    /// 256-color palette. anything other than `#rrggbb` is an error, and leaves the color unchanged
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::{Color, Config, Severity};
+    /// let mut config = Config::default();
+    ///
+    /// config.set_color_hex(Severity::Warning, "#ff8700").unwrap();
+    /// assert_eq!(config.get_color(&Severity::Warning), Color::Color256(208));
+    /// assert!(config.set_color_hex(Severity::Warning, "orange").is_err());
+    /// ```
+    pub fn set_color_hex(&mut self, severity: Severity, hex: &str) -> Result<(), HumantalkError> {
+        let color = parse_hex(hex.trim()).ok_or_else(|| ColorParseError(hex.to_string()))?;
+        self.set_color(severity, color);
+        Ok(())
+    }
+}
+
+/// the 256-color code closest to an RGB color, from the color cube or the grayscale ramp
+pub(crate) fn rgb_to_256(r: u8, g: u8, b: u8) -> u8 {
+    let nearest_level = |value: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| CUBE_LEVELS[i].abs_diff(value))
+            .unwrap_or(0)
+    };
+
+    let (ri, gi, bi) = (nearest_level(r), nearest_level(g), nearest_level(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+    let cube_code = 16 + 36 * ri + 6 * gi + bi;
+
+    // the grayscale ramp runs from 8 to 238 in steps of 10
+    let average = (r as usize + g as usize + b as usize) / 3;
+    let gray_index = (average.saturating_sub(3) / 10).min(23);
+    let gray = (8 + 10 * gray_index) as u8;
+
+    let distance = |(cr, cg, cb): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (a.abs_diff(b) as u32).pow(2);
+        d(r, cr) + d(g, cg) + d(b, cb)
+    };
+
+    if distance((gray, gray, gray)) < distance(cube) {
+        232 + gray_index as u8
+    } else {
+        cube_code as u8
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_named() {
+        assert_eq!(parse_color("black").ok(), Some(Color::Black));
+        assert_eq!(parse_color("Magenta").ok(), Some(Color::Magenta));
+        assert_eq!(parse_color(" white ").ok(), Some(Color::White));
+    }
+
+    #[test]
+    fn test_parse_code() {
+        assert_eq!(parse_color("0").ok(), Some(Color::Color256(0)));
+        assert_eq!(parse_color("255").ok(), Some(Color::Color256(255)));
+        assert!(parse_color("256").is_err());
+        assert!(parse_color("-1").is_err());
+        assert!(parse_color("12a").is_err());
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(parse_color("#000000").ok(), Some(Color::Color256(16)));
+        assert_eq!(parse_color("#ffffff").ok(), Some(Color::Color256(231)));
+        assert_eq!(parse_color("#FF0000").ok(), Some(Color::Color256(196)));
+        assert_eq!(parse_color("#ff8800").ok(), Some(Color::Color256(208)));
+        assert_eq!(parse_color("#808080").ok(), Some(Color::Color256(244)));
+        assert!(parse_color("#fff").is_err());
+        assert!(parse_color("#gg0000").is_err());
+        assert!(parse_color("#ff00é").is_err());
+    }
+
+    #[test]
+    fn test_set_color_hex() {
+        let mut config = Config::default();
+        let info = config.get_color(&Severity::Info);
+
+        assert!(config.set_color_hex(Severity::Error, "#FF0000").is_ok());
+        assert_eq!(config.get_color(&Severity::Error), Color::Color256(196));
+
+        assert!(config.set_color_hex(Severity::Info, "208").is_err());
+        assert!(config.set_color_hex(Severity::Info, "red").is_err());
+        assert!(config.set_color_hex(Severity::Info, "#ff88").is_err());
+        assert_eq!(config.get_color(&Severity::Info), info);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(
+            matches!(parse_color(""), Err(HumantalkError::ParseColor(e)) if e == ColorParseError(String::new()))
+        );
+        assert!(parse_color("purple").is_err());
+        assert!(parse_color("#").is_err());
+    }
+}
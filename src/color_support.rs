@@ -0,0 +1,189 @@
+//! how many colors a sink can show, and downgrading styled lines to fit
+
+use std::borrow::Cow;
+
+use crate::color::CUBE_LEVELS;
+
+/// the colors a sink can show. lines are always styled with the 256-color palette, and a sink with less support
+/// converts them as they are written
+///
+/// # Examples
+/// ```rust,no_run
+/// use humantalk::{ColorSupport, Config, Sink};
+/// let config = Config::default();
+///
+/// let mut sink = Sink::new(std::io::stderr(), true);
+/// sink.set_color_support(ColorSupport::Basic); // for a terminal known to show only 16 colors
+/// config.add_sink(sink);
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ColorSupport {
+    /// no colors or other styling, as on `TERM=dumb`
+    None,
+
+    /// the 16 basic colors, which 256-color codes are mapped to the nearest of
+    Basic,
+
+    /// the full 256-color palette
+    Ansi256,
+}
+
+/// the standard RGB values of the 16 basic colors, as xterm shows them
+const BASIC_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// the escape sequence `console` starts a 256-color foreground with
+const COLOR256_ESCAPE: &str = "\u{1b}[38;5;";
+
+impl ColorSupport {
+    /// the support of the terminal on stdout: none if colors are disabled there (`NO_COLOR`, `TERM=dumb`, or not a
+    /// terminal), the full palette if `COLORTERM` is set or `TERM` mentions 256 colors, and the basic colors for any
+    /// other `TERM`. without a `TERM`, as on windows, the full palette is assumed
+    pub fn detect() -> ColorSupport {
+        ColorSupport::from_env(
+            console::colors_enabled(),
+            std::env::var("TERM").ok().as_deref(),
+            std::env::var("COLORTERM").ok().as_deref(),
+        )
+    }
+
+    fn from_env(colors_enabled: bool, term: Option<&str>, colorterm: Option<&str>) -> ColorSupport {
+        match term {
+            _ if !colors_enabled => ColorSupport::None,
+            Some("dumb") => ColorSupport::None,
+            _ if colorterm.is_some_and(|colorterm| !colorterm.is_empty()) => ColorSupport::Ansi256,
+            Some(term) if !term.contains("256") => ColorSupport::Basic,
+            _ => ColorSupport::Ansi256,
+        }
+    }
+
+    /// `line`, styled with 256-color codes, converted to what this support can show
+    pub(crate) fn convert<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        match self {
+            ColorSupport::None => console::strip_ansi_codes(line),
+            ColorSupport::Basic if line.contains(COLOR256_ESCAPE) => to_basic(line).into(),
+            _ => line.into(),
+        }
+    }
+}
+
+/// `line` with each 256-color foreground replaced by the nearest basic color's escape sequence
+fn to_basic(line: &str) -> String {
+    let mut converted = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(start) = rest.find(COLOR256_ESCAPE) {
+        converted.push_str(&rest[..start]);
+        rest = &rest[start + COLOR256_ESCAPE.len()..];
+
+        let code = rest
+            .find('m')
+            .and_then(|end| rest[..end].parse::<u8>().ok().map(|code| (code, end)));
+        match code {
+            Some((code, end)) => {
+                let basic = nearest_basic(code);
+                let sgr = if basic < 8 {
+                    30 + basic
+                } else {
+                    90 + basic - 8
+                };
+                converted.push_str(&format!("\u{1b}[{}m", sgr));
+                rest = &rest[end + 1..];
+            }
+            None => converted.push_str(COLOR256_ESCAPE),
+        }
+    }
+
+    converted.push_str(rest);
+    converted
+}
+
+/// the basic color, from 0 to 15, closest to the 256-color `code`
+fn nearest_basic(code: u8) -> u8 {
+    let (r, g, b) = match code {
+        0..=15 => return code,
+        16..=231 => {
+            let index = (code - 16) as usize;
+            (
+                CUBE_LEVELS[index / 36],
+                CUBE_LEVELS[index / 6 % 6],
+                CUBE_LEVELS[index % 6],
+            )
+        }
+        _ => {
+            let gray = 8 + 10 * (code - 232);
+            (gray, gray, gray)
+        }
+    };
+
+    let distance = |&(br, bg, bb): &(u8, u8, u8)| {
+        let d = |a: u8, b: u8| (a.abs_diff(b) as u32).pow(2);
+        d(r, br) + d(g, bg) + d(b, bb)
+    };
+
+    (0..BASIC_RGB.len())
+        .min_by_key(|&i| distance(&BASIC_RGB[i]))
+        .unwrap_or(0) as u8
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_color_support() {
+        assert_eq!(
+            ColorSupport::from_env(false, Some("xterm-256color"), None),
+            ColorSupport::None
+        );
+        assert_eq!(
+            ColorSupport::from_env(true, Some("dumb"), Some("truecolor")),
+            ColorSupport::None
+        );
+        assert_eq!(
+            ColorSupport::from_env(true, Some("xterm"), None),
+            ColorSupport::Basic
+        );
+        assert_eq!(
+            ColorSupport::from_env(true, Some("xterm"), Some("truecolor")),
+            ColorSupport::Ansi256
+        );
+        assert_eq!(
+            ColorSupport::from_env(true, Some("xterm-256color"), None),
+            ColorSupport::Ansi256
+        );
+        assert_eq!(
+            ColorSupport::from_env(true, None, None),
+            ColorSupport::Ansi256
+        );
+
+        assert_eq!(nearest_basic(1), 1);
+        assert_eq!(nearest_basic(208), 3);
+        assert_eq!(nearest_basic(196), 9);
+        assert_eq!(nearest_basic(244), 8);
+
+        let line = "\u{1b}[38;5;1m\u{1b}[1m[error]\u{1b}[0m \u{1b}[38;5;208mboom\u{1b}[0m";
+        assert_eq!(ColorSupport::Ansi256.convert(line), line);
+        assert_eq!(
+            ColorSupport::Basic.convert(line),
+            "\u{1b}[31m\u{1b}[1m[error]\u{1b}[0m \u{1b}[33mboom\u{1b}[0m"
+        );
+        assert_eq!(ColorSupport::None.convert(line), "[error] boom");
+    }
+}
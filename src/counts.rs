@@ -0,0 +1,153 @@
+//! counting how many messages of each severity have been logged
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use console::style;
+
+use crate::{ColorToColor256, Config, Severity};
+
+/// exit code suggested by `Config::suggested_exit_code` once an error has been logged
+pub const ERROR_EXIT_CODE: i32 = 1;
+
+/// number of messages logged at each severity, shared between clones of a config
+#[derive(Debug, Default)]
+pub(crate) struct Counts {
+    error: AtomicUsize,
+    warning: AtomicUsize,
+    info: AtomicUsize,
+    debug: AtomicUsize,
+    trace: AtomicUsize,
+}
+
+impl Counts {
+    fn counter(&self, severity: &Severity) -> &AtomicUsize {
+        match severity {
+            Severity::Error => &self.error,
+            Severity::Warning => &self.warning,
+            Severity::Info => &self.info,
+            Severity::Debug => &self.debug,
+            Severity::Trace => &self.trace,
+        }
+    }
+
+    pub(crate) fn record(&self, severity: &Severity) {
+        self.counter(severity).fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn get(&self, severity: &Severity) -> usize {
+        self.counter(severity).load(Ordering::Relaxed)
+    }
+}
+
+impl Config {
+    /// how many messages of `severity` have been logged through this config or its clones. messages hidden by quiet,
+    /// silent or `min_severity` are still counted
+    pub fn count(&self, severity: &Severity) -> usize {
+        self.counts.get(severity)
+    }
+
+    /// the exit code a program should finish with: `ERROR_EXIT_CODE` (1) if any error has been logged, 0 otherwise.
+    /// warnings alone do not make the exit code non-zero
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// config.error("could not read settings.toml");
+    /// std::process::exit(config.suggested_exit_code()); // exits with 1
+    /// ```
+    pub fn suggested_exit_code(&self) -> i32 {
+        if self.count(&Severity::Error) > 0 {
+            ERROR_EXIT_CODE
+        } else {
+            0
+        }
+    }
+}
+
+impl Config {
+    /// a one-line summary of how many messages have been logged, like `Summary: 0 errors, 2 warnings, 15 info`, with each
+    /// count in its severity's color. debug and trace messages are included only if there were any
+    pub fn summary(&self) -> String {
+        let mut parts = vec![];
+
+        for (severity, singular, plural) in [
+            (Severity::Error, "error", "errors"),
+            (Severity::Warning, "warning", "warnings"),
+            (Severity::Info, "info", "info"),
+            (Severity::Debug, "debug", "debug"),
+            (Severity::Trace, "trace", "trace"),
+        ] {
+            let count = self.count(&severity);
+            if matches!(severity, Severity::Debug | Severity::Trace) && count == 0 {
+                continue;
+            }
+
+            let part = format!("{} {}", count, if count == 1 { singular } else { plural });
+            parts.push(
+                style(part)
+                    .color256(self.get_color(&severity).to_color256())
+                    .force_styling(true)
+                    .to_string(),
+            );
+        }
+
+        format!("Summary: {}", parts.join(", "))
+    }
+
+    /// print the `summary` line, unless in silent mode
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// config.warning("2 files skipped");
+    /// config.print_summary(); // Summary: 0 errors, 1 warning, 0 info
+    /// ```
+    pub fn print_summary(&self) {
+        if self.silent {
+            return;
+        }
+
+        let _ = self.lock_output().write_line(
+            &Severity::Info,
+            &self.summary(),
+            self.buffers(&Severity::Info),
+        );
+    }
+}
+
+#[cfg(all(test, not(feature = "max_level_warn")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_suggested_exit_code() {
+        let mut config = Config::default();
+        config.set_silent(true);
+
+        config.warning("deprecated flag");
+        assert_eq!(config.suggested_exit_code(), 0);
+
+        let clone = config.clone();
+        crate::hterror!(clone, "{} failed", "upload");
+        assert_eq!(config.count(&Severity::Error), 1);
+        assert_eq!(config.count(&Severity::Warning), 1);
+        assert_eq!(config.suggested_exit_code(), ERROR_EXIT_CODE);
+
+        assert_eq!(
+            console::strip_ansi_codes(&config.summary()),
+            "Summary: 1 error, 1 warning, 0 info"
+        );
+        config.write(Severity::Debug, "details");
+        config.info("done");
+        config.info("really done");
+        assert!(config.summary().contains("\u{1b}[38;5;1m1 error\u{1b}[0m"));
+        assert_eq!(
+            console::strip_ansi_codes(&config.summary()),
+            "Summary: 1 error, 1 warning, 2 info, 1 debug"
+        );
+    }
+}
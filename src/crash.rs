@@ -0,0 +1,1319 @@
+//! fatal errors, and the crash reports they leave behind
+
+use std::{
+    backtrace::Backtrace,
+    error::Error,
+    fs::{File, OpenOptions},
+    io::Write,
+    panic::AssertUnwindSafe,
+    path::{Path, PathBuf},
+    sync::{Arc, PoisonError},
+};
+
+use console::style;
+use thetime::{System, Time};
+
+use crate::{
+    clock, json_string, recent::LogEntry, source::error_causes, AppInfo, Config, FatalText,
+    HowToBugReport, HumantalkError, MachineInfo, CRASH_REPORT_FILE, TIMESTAMP_FORMAT,
+};
+
+/// exit code used by `fatal_error`
+pub const FATAL_EXIT_CODE: i32 = 3;
+
+/// start of the header line written above each crash report
+const CRASH_HEADER: &str = "==== crash at ";
+
+/// prefixes of the backtrace frames left out of crash reports: capturing the backtrace and handling a panic
+const INTERNAL_FRAMES: &[&str] = &[
+    "std::backtrace",
+    "std::panicking",
+    "std::sys::backtrace",
+    "core::panicking",
+    "__rustc::",
+    "rust_begin_unwind",
+    "<alloc::boxed::Box",
+];
+
+/// how `fatal_error` treats crash reports left behind by earlier crashes
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum CrashReportMode {
+    /// add each crash to the end of the crash report file, under a `==== crash at ... ====` header
+    Append,
+
+    /// write each crash to its own file, named like `crash_report-20240502-103122.log`
+    NewFilePerCrash,
+
+    /// replace the previous crash report
+    Overwrite,
+}
+
+/// the format `fatal_error` writes crash reports in. the console output is always human-readable
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum CrashReportFormat {
+    /// plain text, as printed to the console
+    Plain,
+
+    /// one JSON document per crash, on a single line (so appended reports form JSON Lines). the fields are:
+    /// - `timestamp`: time of the crash, as `2024-05-02T10:31:22Z`
+    /// - `message`: the message passed to `fatal_error`
+    /// - `caused_by`: array of the messages of the errors that caused the crash, outermost first
+    /// - `exit_code`: the code the process exits with
+    /// - `bug_report`: object with the bug report's `message` and `url`
+    /// - `app`: object with the application's `name`, `version`, `git_commit` and `build_date`, or `null` if not set
+    /// - `machine`: object with `family`, `os`, `arch`, `environment`, `cpu_count`, `memory_bytes`,
+    ///   `rustc_version`, `llvm_version`, `humantalk_version`, `humantalk_features` (array of strings), `hostname` and
+    ///   `username`. `cpu_count`, `memory_bytes`, `llvm_version`, `hostname` and `username` may be `null`
+    /// - `context`: object with the config's `scope` and `with_field` fields, then the key/value pairs set with
+    ///   `Config::set_crash_context`
+    /// - `recent_log`: array of the most recent messages, each with `timestamp`, `severity` and `message`
+    /// - `backtrace`: the backtrace as a string, or `null` if it was not captured
+    Json,
+}
+
+/// what `fatal_error` does once the crash has been reported
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ExitBehavior {
+    /// exit the process with the report's exit code
+    Exit,
+
+    /// panic with the message instead, so tests can catch the fatal error with `std::panic::catch_unwind`
+    Panic,
+}
+
+/// the stream `fatal_error` prints its human-readable report to
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum FatalStream {
+    /// standard output
+    Stdout,
+
+    /// standard error, so the report reaches the user even when stdout is piped or captured
+    #[default]
+    Stderr,
+}
+
+/// the sections of a plaintext crash report, which are colored differently on the console
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum Section {
+    Message,
+    CausedBy,
+    PlatformInfo,
+    Context,
+    RecentLog,
+    Backtrace,
+}
+
+/// everything `fatal_error` reports about a crash, as built by `Config::build_crash_report`
+#[derive(Debug, Clone)]
+pub struct CrashReport {
+    /// the message passed to `fatal_error`
+    pub message: String,
+
+    /// messages of the errors that caused the crash, outermost first, when it was reported with `fatal_error_from`
+    pub causes: Vec<String>,
+
+    /// where the user is asked to report the crash
+    pub bug_report: HowToBugReport,
+
+    /// time of the crash, as `2024-05-02T10:31:22Z`
+    pub timestamp: String,
+
+    /// the application's info, if it was set with `Config::set_app_info`
+    pub app_info: Option<AppInfo>,
+
+    /// platform info, as returned by `Config::machine_info_struct`
+    pub machine_info: MachineInfo,
+
+    /// app-specific key/value pairs: the config's scope and fields from `Config::with_field`, then those set with
+    /// `Config::set_crash_context`, in the order they were first set
+    pub context: Vec<(String, String)>,
+
+    /// the most recent messages written before the crash, oldest first
+    pub recent_log: Vec<LogEntry>,
+
+    /// backtrace of the code that crashed, if backtraces are captured
+    pub backtrace: Option<String>,
+
+    /// the code the process exits with
+    pub exit_code: i32,
+
+    time: System,
+}
+
+impl CrashReport {
+    /// the `[PLATFORM INFO]` section's contents: the application's info, if set, then the machine info
+    fn platform_info(&self) -> String {
+        match &self.app_info {
+            Some(app) => format!("app: {}\n{}", app, self.machine_info),
+            None => self.machine_info.to_string(),
+        }
+    }
+
+    /// the sections of the plaintext report in order, each ending with a newline, leaving out those with nothing in
+    /// them. the message is surrounded by the fatal error's `text`, with the bug report's `url` as given, and `path` if
+    /// the report was saved to a file
+    fn sections(&self, text: &FatalText, url: &str, path: Option<&str>) -> Vec<(Section, String)> {
+        let mut sections = vec![(
+            Section::Message,
+            text.render(&self.message, &self.bug_report.message, url, path),
+        )];
+
+        if !self.causes.is_empty() {
+            sections.push((Section::CausedBy, caused_by(&self.causes)));
+        }
+
+        sections.push((
+            Section::PlatformInfo,
+            format!("[PLATFORM INFO]\n{}\n", self.platform_info()),
+        ));
+
+        if !self.context.is_empty() {
+            sections.push((Section::Context, context(&self.context)));
+        }
+
+        if !self.recent_log.is_empty() {
+            let mut recent_log = "[RECENT LOG]\n".to_string();
+            for entry in &self.recent_log {
+                recent_log.push_str(&format!("{}\n", entry));
+            }
+            sections.push((Section::RecentLog, recent_log));
+        }
+
+        if let Some(backtrace) = &self.backtrace {
+            sections.push((Section::Backtrace, format!("[BACKTRACE]\n{}\n", backtrace)));
+        }
+
+        sections
+    }
+
+    /// the whole plaintext report, as its sections one after another
+    fn text(&self, text: &FatalText, url: &str, path: Option<&str>) -> String {
+        self.sections(text, url, path)
+            .into_iter()
+            .map(|(_, section)| section)
+            .collect()
+    }
+
+    /// the plaintext report, headed with the time of the crash, as written to a crash report file at `path` with the
+    /// fatal error's surrounding `text`
+    fn plain(&self, path: &Path, text: &FatalText) -> String {
+        format!(
+            "{}{} ====\n{}\n",
+            CRASH_HEADER,
+            self.timestamp,
+            self.text(
+                text,
+                &self.bug_report.url,
+                Some(&path.display().to_string())
+            )
+        )
+    }
+
+    /// the report as a single line of JSON, see `CrashReportFormat::Json` for the fields
+    fn json(&self) -> String {
+        let recent_log = self
+            .recent_log
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{{\"timestamp\":{},\"severity\":{},\"message\":{}}}",
+                    json_string(&entry.timestamp()),
+                    json_string(&entry.severity.to_string()),
+                    json_string(&entry.message)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(",");
+
+        format!(
+            "{{\"timestamp\":{},\"message\":{},\"caused_by\":[{}],\"exit_code\":{},\"bug_report\":{{\"message\":{},\"url\":{}}},\
+\"app\":{},\"machine\":{},\"context\":{{{}}},\"recent_log\":[{}],\"backtrace\":{}}}\n",
+            json_string(&self.timestamp),
+            json_string(&self.message),
+            self.causes.iter().map(|cause| json_string(cause)).collect::<Vec<String>>().join(","),
+            self.exit_code,
+            json_string(&self.bug_report.message),
+            json_string(&self.bug_report.url),
+            self.app_info.as_ref().map_or("null".to_string(), AppInfo::json),
+            self.machine_info.json(),
+            self.context
+                .iter()
+                .map(|(key, value)| format!("{}:{}", json_string(key), json_string(value)))
+                .collect::<Vec<String>>()
+                .join(","),
+            recent_log,
+            self.backtrace.as_deref().map_or("null".to_string(), json_string)
+        )
+    }
+}
+
+impl Config {
+    /// set the path `fatal_error` writes the crash report to
+    pub fn set_crash_report_path(&mut self, path: PathBuf) {
+        self.crash_report_path = Some(path);
+    }
+
+    /// set a key/value pair, such as the last request ID, listed under `[CONTEXT]` in crash reports. setting a key
+    /// again replaces its value
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::Config;
+    /// let mut config = Config::default();
+    ///
+    /// config.set_crash_context("config file", "/etc/myapp.toml");
+    /// config.set_crash_context("last request", "7f3e2a");
+    /// ```
+    pub fn set_crash_context(&mut self, key: &str, value: &str) {
+        match self
+            .crash_context
+            .iter_mut()
+            .find(|(existing, _)| existing == key)
+        {
+            Some((_, existing)) => *existing = value.to_string(),
+            None => self
+                .crash_context
+                .push((key.to_string(), value.to_string())),
+        }
+    }
+
+    /// set how existing crash reports are treated
+    pub fn set_crash_report_mode(&mut self, mode: CrashReportMode) {
+        self.crash_report_mode = mode;
+    }
+
+    /// set the format crash reports are written in
+    pub fn set_crash_report_format(&mut self, format: CrashReportFormat) {
+        self.crash_report_format = format;
+    }
+
+    /// set whether `fatal_error` writes a crash report file. when off, the report is only printed and the filesystem is
+    /// never touched, for read-only or sandboxed environments
+    pub fn set_write_crash_log(&mut self, write_crash_log: bool) {
+        self.write_crash_log = write_crash_log;
+    }
+
+    /// set how many crash reports are kept before the oldest are pruned
+    pub fn set_crash_report_retention(&mut self, retention: usize) {
+        self.crash_report_retention = Some(retention);
+    }
+
+    /// set whether a backtrace is included in the crash report
+    pub fn set_capture_backtrace(&mut self, capture: bool) {
+        self.capture_backtrace = capture;
+    }
+
+    /// set what `fatal_error` does once the crash has been reported
+    pub fn set_exit_behavior(&mut self, behavior: ExitBehavior) {
+        self.exit_behavior = behavior;
+    }
+
+    /// set the stream `fatal_error` prints its report to, stderr by default. the crash report file is unaffected
+    pub fn set_fatal_stream(&mut self, stream: FatalStream) {
+        self.fatal_stream = stream;
+    }
+
+    /// the locations a crash report is attempted at, in order: the configured path (or `crash_report.log` in the current directory), then the OS temp dir
+    fn crash_report_candidates(&self) -> Vec<PathBuf> {
+        let preferred = match &self.crash_report_path {
+            Some(path) => path.clone(),
+            None => PathBuf::from(CRASH_REPORT_FILE),
+        };
+
+        vec![preferred, std::env::temp_dir().join(CRASH_REPORT_FILE)]
+    }
+
+    /// the configured bug report, or a generic one if none has been set
+    fn bug_report(&self) -> HowToBugReport {
+        match &self.bug_report {
+            Some(x) => HowToBugReport {
+                message: self.fill_app(&x.message),
+                url: self.fill_app(&x.url),
+            },
+            None => HowToBugReport {
+                message: "Oh no! The program has crashed".to_string(),
+                url: "the appropriate place".to_string(),
+            },
+        }
+    }
+
+    /// capture a backtrace of the caller, with the frames inside humantalk and `std::backtrace` trimmed from the top.
+    /// `None` if backtraces have been turned off with `set_capture_backtrace(false)`
+    fn backtrace(&self) -> Option<String> {
+        if !self.capture_backtrace {
+            return None;
+        }
+
+        Some(trim_backtrace(&Backtrace::force_capture().to_string()))
+    }
+
+    /// the plaintext report `fatal_error` would give for a crash with `message`, without printing, writing or exiting:
+    /// the message and how to report the bug, any causes and context, platform info, the recent log and the backtrace.
+    /// this is the same text printed to the console and written to crash report files, for showing elsewhere, such as
+    /// in a dialog
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// let report = config.crash_report_text("the window could not be created");
+    /// assert!(report.contains("[PLATFORM INFO]"));
+    /// ```
+    pub fn crash_report_text(&self, message: &str) -> String {
+        let report = self.build_crash_report(message);
+        report.text(&self.fatal_text, &report.bug_report.url, None)
+    }
+
+    /// gather everything `fatal_error` would report about a crash with `message`, without printing, writing or exiting
+    pub fn build_crash_report(&self, message: &str) -> CrashReport {
+        let time = clock::now();
+
+        CrashReport {
+            message: message.to_string(),
+            causes: vec![],
+            bug_report: self.bug_report(),
+            app_info: self.app_info.clone(),
+            timestamp: time.strftime(TIMESTAMP_FORMAT),
+            machine_info: self.machine_info_struct(),
+            context: self.report_context(),
+            recent_log: self
+                .recent
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .entries()
+                .cloned()
+                .collect(),
+            backtrace: self.backtrace(),
+            exit_code: FATAL_EXIT_CODE,
+            time,
+        }
+    }
+
+    /// write a crash report, in the configured format, to an already opened crash report file at `path`
+    fn write_crash_report(
+        &self,
+        report: &CrashReport,
+        file: &mut File,
+        path: &Path,
+    ) -> std::io::Result<()> {
+        let contents = match self.crash_report_format {
+            CrashReportFormat::Plain => report.plain(path, &self.fatal_text),
+            CrashReportFormat::Json => report.json(),
+        };
+
+        file.write_all(contents.as_bytes())
+    }
+
+    /// whether a crash report file is written. never on browser wasm targets, which have no filesystem
+    fn writes_crash_log(&self) -> bool {
+        self.write_crash_log && !clock::WASM
+    }
+
+    /// end the process according to the configured `ExitBehavior`. browser wasm targets cannot exit, so they always
+    /// panic
+    pub(crate) fn exit(&self, code: i32, message: &str) -> ! {
+        match self.exit_behavior {
+            ExitBehavior::Exit if !clock::WASM => std::process::exit(code),
+            ExitBehavior::Exit => panic!("fatal error: {}", message),
+            ExitBehavior::Panic => panic!("fatal error: {}", message),
+        }
+    }
+
+    /// error fatally, crashing the program. then exits with error code `3`, indincating that erroring out has succeeded.
+    /// with `ExitBehavior::Panic` it panics instead of exiting. use `report_fatal` to report without exiting
+    pub fn fatal_error(&self, message: &str) -> ! {
+        self.fatal_error_with_code(message, FATAL_EXIT_CODE)
+    }
+
+    /// error fatally like `fatal_error`, with `error`'s message. each error in its `source()` chain is listed under a
+    /// `[CAUSED BY]` header, in the console output and the crash report
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// if let Err(e) = std::fs::read_to_string("settings.toml") {
+    ///     config.fatal_error_from(&e);
+    /// }
+    /// ```
+    pub fn fatal_error_from<E: Error>(&self, error: &E) -> ! {
+        let report = self.build_error_report(error.to_string(), error_causes(error));
+        let code = self.report_crash(&report);
+
+        self.exit(code, &report.message)
+    }
+
+    /// a crash report for an error with `message`, caused by the errors with messages `causes`
+    pub(crate) fn build_error_report(&self, message: String, causes: Vec<String>) -> CrashReport {
+        let mut report = self.build_crash_report(&message);
+        report.causes = causes;
+        report
+    }
+
+    /// error fatally like `fatal_error`, but exit with `code`. the code is clamped to `1..=255`, the range every
+    /// platform reports faithfully, so a fatal error never looks like success
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// config.fatal_error_with_code("could not parse arguments", 64); // EX_USAGE
+    /// ```
+    pub fn fatal_error_with_code(&self, message: &str, code: i32) -> ! {
+        let mut report = self.build_crash_report(message);
+        report.exit_code = code.clamp(1, 255);
+        let code = self.report_crash(&report);
+
+        self.exit(code, message)
+    }
+
+    /// report a fatal error like `fatal_error`, printing it and writing the crash report, but without exiting. returns
+    /// the path of the crash report file, so a supervisor can log the failure and restart the failed task instead of
+    /// the whole process. the `on_fatal` hooks still run. with `set_write_crash_log(false)` the error is still printed,
+    /// but an `Unsupported` error is returned as there is no file
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// match config.report_fatal("worker 3 lost its database connection") {
+    ///     Ok(path) => config.info(&format!("restarting worker 3, see {}", path.display())),
+    ///     Err(e) => config.warning(&format!("restarting worker 3, no crash report written: {}", e)),
+    /// }
+    /// ```
+    pub fn report_fatal(&self, message: &str) -> Result<PathBuf, HumantalkError> {
+        let path = self
+            .print_crash(&self.build_crash_report(message))?
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    "crash report files are turned off",
+                )
+            })?;
+        Ok(path)
+    }
+
+    /// print `report`, write it to the crash report file and run the `on_fatal` hooks, returning the exit code to use.
+    /// a crash report file that could not be written is reported on the fatal stream, but does not change the exit code
+    pub(crate) fn report_crash(&self, report: &CrashReport) -> i32 {
+        let _ = self.print_crash(report);
+        report.exit_code
+    }
+
+    /// print `report`, write it to the crash report file and run the `on_fatal` hooks, returning the file's path, or
+    /// `None` if crash report files are turned off
+    fn print_crash(&self, report: &CrashReport) -> std::io::Result<Option<PathBuf>> {
+        self.flush();
+
+        let crash_file = if self.writes_crash_log() {
+            open_crash_report(
+                &self.crash_report_candidates(),
+                self.crash_report_mode,
+                self.crash_report_format,
+                self.crash_report_retention,
+                &report.time,
+            )
+        } else {
+            None
+        };
+
+        let stderr = self.fatal_stream == FatalStream::Stderr;
+        let style = |text: String| match stderr {
+            true => style(text).for_stderr(),
+            false => style(text),
+        };
+
+        // written rather than printed, so a closed stream does not panic before the crash report is written
+        let mut console: Box<dyn Write> = match self.fatal_stream {
+            FatalStream::Stdout => Box::new(std::io::stdout()),
+            FatalStream::Stderr => Box::new(std::io::stderr()),
+        };
+
+        let path = crash_file
+            .as_ref()
+            .map(|(_, path)| path.display().to_string());
+        let url = self.link(&report.bug_report.url, &report.bug_report.url);
+        for (section, text) in report.sections(&self.fatal_text, &url, path.as_deref()) {
+            let styled = match section {
+                Section::Message | Section::CausedBy => style(text).red(),
+                Section::PlatformInfo | Section::Context => style(text).cyan(),
+                // its messages were written to the console as they happened
+                Section::RecentLog => continue,
+                Section::Backtrace => style(text).dim(),
+            };
+            let _ = writeln!(console, "{}", styled);
+        }
+
+        let result = match crash_file {
+            Some((mut debug_file, path)) => {
+                match self.write_crash_report(report, &mut debug_file, &path) {
+                    Ok(()) => Ok(Some(path)),
+                    Err(e) => {
+                        let _ = writeln!(
+                            console,
+                            "Failed to write to debug file ({}) - just copy the information displayed above.",
+                            e
+                        );
+
+                        Err(e)
+                    }
+                }
+            }
+            None if !self.writes_crash_log() => Ok(None),
+            None => {
+                let _ = writeln!(
+                    console,
+                    "Failed to create debug file - just copy the information displayed above."
+                );
+
+                Err(std::io::Error::other(
+                    "could not create a crash report file in any candidate location",
+                ))
+            }
+        };
+
+        self.run_fatal_hooks(report);
+
+        result
+    }
+
+    /// register a callback to run when `fatal_error` is called, after the crash report has been written but before the
+    /// process exits. callbacks run in the order they were registered, and one panicking does not stop the rest
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::Config;
+    /// let mut config = Config::default();
+    ///
+    /// config.on_fatal(|report| {
+    ///     eprintln!("releasing lock after crash: {}", report.message);
+    /// });
+    /// ```
+    pub fn on_fatal(&mut self, hook: impl Fn(&CrashReport) + Send + Sync + 'static) {
+        self.fatal_hooks.0.push(Arc::new(hook));
+    }
+
+    fn run_fatal_hooks(&self, report: &CrashReport) {
+        for hook in &self.fatal_hooks.0 {
+            let _ = std::panic::catch_unwind(AssertUnwindSafe(|| hook(report)));
+        }
+    }
+}
+
+/// callbacks registered with `Config::on_fatal`. clones of a config get a copy of the list, so hooks added to a clone
+/// do not affect the original
+#[derive(Clone, Default)]
+pub(crate) struct FatalHooks(Vec<FatalHook>);
+
+type FatalHook = Arc<dyn Fn(&CrashReport) + Send + Sync>;
+
+impl std::fmt::Debug for FatalHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "FatalHooks({})", self.0.len())
+    }
+}
+
+/// the `[CAUSED BY]` section listing `causes`, one indented cause per line, or nothing if there are none
+fn caused_by(causes: &[String]) -> String {
+    if causes.is_empty() {
+        return String::new();
+    }
+
+    let mut section = "[CAUSED BY]\n".to_string();
+    for cause in causes {
+        section.push_str(&format!("  {}\n", cause));
+    }
+
+    section
+}
+
+/// the `[CONTEXT]` section listing the `key: value` pairs in `context`, one per line, or nothing if there are none
+fn context(context: &[(String, String)]) -> String {
+    if context.is_empty() {
+        return String::new();
+    }
+
+    let mut section = "[CONTEXT]\n".to_string();
+    for (key, value) in context {
+        section.push_str(&format!("{}: {}\n", key, value));
+    }
+
+    section
+}
+
+/// drop the leading frames of a formatted backtrace that belong to `std::backtrace`, the panic machinery or humantalk
+/// itself, so it starts at the code that crashed. frames are renumbered from zero
+fn trim_backtrace(backtrace: &str) -> String {
+    let mut frames: Vec<Vec<&str>> = vec![];
+    for line in backtrace.lines() {
+        let trimmed = line.trim_start();
+        let is_frame = trimmed.split_once(": ").is_some_and(|(index, _)| {
+            !index.is_empty() && index.chars().all(|c| c.is_ascii_digit())
+        });
+
+        match frames.last_mut() {
+            Some(frame) if !is_frame => frame.push(line),
+            _ => frames.push(vec![line]),
+        }
+    }
+
+    let internal = |frame: &Vec<&str>| {
+        let symbol = frame[0]
+            .trim_start()
+            .split_once(": ")
+            .map_or("", |(_, symbol)| symbol);
+        INTERNAL_FRAMES
+            .iter()
+            .any(|prefix| symbol.starts_with(prefix))
+            || symbol.starts_with("humantalk::")
+    };
+
+    frames
+        .iter()
+        .skip_while(|frame| internal(frame))
+        .enumerate()
+        .flat_map(|(index, frame)| {
+            let (_, symbol) = frame[0]
+                .trim_start()
+                .split_once(": ")
+                .unwrap_or(("", frame[0]));
+            std::iter::once(format!("{:>4}: {}", index, symbol))
+                .chain(frame[1..].iter().map(|line| line.to_string()))
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// open the crash report file at the first candidate path that can be written to, according to `mode`.
+/// if `retention` is set, older reports are pruned first so that the new one brings the total up to the limit
+fn open_crash_report(
+    candidates: &[PathBuf],
+    mode: CrashReportMode,
+    format: CrashReportFormat,
+    retention: Option<usize>,
+    time: &System,
+) -> Option<(File, PathBuf)> {
+    candidates.iter().find_map(|path| match mode {
+        CrashReportMode::Append => {
+            if let Some(keep) = retention {
+                let _ = trim_crash_log(path, format, keep);
+            }
+
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .ok()
+                .map(|file| (file, path.clone()))
+        }
+        CrashReportMode::Overwrite => File::create(path).ok().map(|file| (file, path.clone())),
+        CrashReportMode::NewFilePerCrash => {
+            if let Some(keep) = retention {
+                let _ = prune_crash_files(path, keep);
+            }
+
+            let stamp = time.strftime("%Y%m%d-%H%M%S");
+
+            // two crashes within the same second get a numeric suffix rather than clobbering each other
+            (0..100)
+                .find_map(|attempt| {
+                    let unique = match attempt {
+                        0 => stamp.clone(),
+                        n => format!("{}-{}", stamp, n),
+                    };
+                    let path = timestamped_path(path, &unique);
+
+                    match OpenOptions::new().write(true).create_new(true).open(&path) {
+                        Ok(file) => Some(Some((file, path))),
+                        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => None,
+                        Err(_) => Some(None),
+                    }
+                })
+                .flatten()
+        }
+    })
+}
+
+/// drop the oldest reports from an appended crash report file, leaving room for one more within `keep`
+fn trim_crash_log(path: &Path, format: CrashReportFormat, keep: usize) -> std::io::Result<()> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+
+    let starts: Vec<usize> = match format {
+        CrashReportFormat::Plain => contents
+            .match_indices(CRASH_HEADER)
+            .map(|(i, _)| i)
+            .collect(),
+        CrashReportFormat::Json => std::iter::once(0)
+            .chain(contents.match_indices('\n').map(|(i, _)| i + 1))
+            .filter(|&i| i < contents.len())
+            .collect(),
+    };
+    let excess = starts.len().saturating_sub(keep.saturating_sub(1));
+    if excess == 0 {
+        return Ok(());
+    }
+
+    let cut = starts.get(excess).copied().unwrap_or(contents.len());
+    std::fs::write(path, &contents[cut..])
+}
+
+/// delete the oldest timestamped crash report files derived from `path`, leaving room for one more within `keep`
+fn prune_crash_files(path: &Path, keep: usize) -> std::io::Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let prefix = format!(
+        "{}-",
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    );
+
+    let mut reports: Vec<(String, PathBuf)> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|report| report.extension() == path.extension())
+        .filter_map(|report| {
+            let stem = report.file_stem()?.to_string_lossy().to_string();
+            let stamp = stem.strip_prefix(&prefix)?;
+
+            if stamp.starts_with(|c: char| c.is_ascii_digit()) {
+                Some((stem, report))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    // timestamps sort lexically, and a same-second `-n` suffix sorts after its unsuffixed stem
+    reports.sort();
+
+    let excess = reports.len().saturating_sub(keep.saturating_sub(1));
+    for (_, report) in reports.iter().take(excess) {
+        std::fs::remove_file(report)?;
+    }
+
+    Ok(())
+}
+
+/// insert a timestamp into a crash report path, so `crash_report.log` becomes `crash_report-20240502-103122.log`
+fn timestamped_path(path: &Path, stamp: &str) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "crash_report".to_string());
+
+    let name = match path.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, stamp, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, stamp),
+    };
+
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_crash_report_candidates() {
+        let mut config = Config::default();
+        assert_eq!(
+            config.crash_report_candidates(),
+            vec![
+                PathBuf::from(CRASH_REPORT_FILE),
+                std::env::temp_dir().join(CRASH_REPORT_FILE)
+            ]
+        );
+
+        config.set_crash_report_path(PathBuf::from("/var/log/app/crash.log"));
+        assert_eq!(
+            config.crash_report_candidates()[0],
+            PathBuf::from("/var/log/app/crash.log")
+        );
+    }
+
+    #[test]
+    fn test_crash_report_fallback() {
+        let dir = crate::test::scratch_dir("fallback");
+        let unwritable = dir.join("missing").join(CRASH_REPORT_FILE);
+        let fallback = dir.join(CRASH_REPORT_FILE);
+
+        let time = clock::now();
+        let (_, path) = open_crash_report(
+            &[unwritable.clone(), fallback.clone()],
+            CrashReportMode::Overwrite,
+            CrashReportFormat::Plain,
+            None,
+            &time,
+        )
+        .unwrap();
+        assert_eq!(path, fallback);
+        assert!(fallback.exists());
+
+        assert!(open_crash_report(
+            &[unwritable],
+            CrashReportMode::Overwrite,
+            CrashReportFormat::Plain,
+            None,
+            &time
+        )
+        .is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    fn crash_twice(config: &Config) -> Vec<PathBuf> {
+        let time = clock::now();
+        ["first crash", "second crash"]
+            .iter()
+            .map(|message| {
+                let (mut file, path) = open_crash_report(
+                    &config.crash_report_candidates(),
+                    config.crash_report_mode,
+                    config.crash_report_format,
+                    config.crash_report_retention,
+                    &time,
+                )
+                .unwrap();
+                let report = config.build_crash_report(message);
+                config
+                    .write_crash_report(&report, &mut file, &path)
+                    .unwrap();
+                path
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_crash_report_modes() {
+        let dir = crate::test::scratch_dir("modes");
+        let mut config = Config::default();
+        config.set_crash_report_path(dir.join(CRASH_REPORT_FILE));
+
+        let paths = crash_twice(&config);
+        assert_eq!(paths[0], paths[1]);
+        let contents = std::fs::read_to_string(&paths[0]).unwrap();
+        assert_eq!(contents.matches("==== crash at ").count(), 2);
+        assert!(contents.find("first crash").unwrap() < contents.find("second crash").unwrap());
+
+        config.set_crash_report_mode(CrashReportMode::Overwrite);
+        let paths = crash_twice(&config);
+        let contents = std::fs::read_to_string(&paths[0]).unwrap();
+        assert!(!contents.contains("first crash"));
+        assert!(contents.contains("second crash"));
+
+        config.set_crash_report_mode(CrashReportMode::NewFilePerCrash);
+        let paths = crash_twice(&config);
+        assert_ne!(paths[0], paths[1]);
+        assert!(std::fs::read_to_string(&paths[0])
+            .unwrap()
+            .contains("first crash"));
+        assert!(std::fs::read_to_string(&paths[1])
+            .unwrap()
+            .contains("second crash"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_crash_report_retention() {
+        let dir = crate::test::scratch_dir("retention");
+        let mut config = Config::default();
+        config.set_crash_report_path(dir.join(CRASH_REPORT_FILE));
+        config.set_crash_report_retention(3);
+
+        crash_twice(&config);
+        crash_twice(&config);
+        let contents = std::fs::read_to_string(dir.join(CRASH_REPORT_FILE)).unwrap();
+        assert_eq!(contents.matches(CRASH_HEADER).count(), 3);
+        assert!(contents.starts_with(CRASH_HEADER));
+
+        config.set_crash_report_mode(CrashReportMode::NewFilePerCrash);
+        crash_twice(&config);
+        let paths = crash_twice(&config);
+        let files = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter(|entry| entry.as_ref().unwrap().path() != dir.join(CRASH_REPORT_FILE))
+            .count();
+        assert_eq!(files, 3);
+        assert!(paths.iter().all(|path| path.exists()));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_backtrace() {
+        let dir = crate::test::scratch_dir("backtrace");
+        let path = dir.join(CRASH_REPORT_FILE);
+        let mut config = Config::default();
+        config.set_capture_backtrace(true);
+
+        let report = config.build_crash_report("crashed");
+        let mut file = File::create(&path).unwrap();
+        config
+            .write_crash_report(&report, &mut file, &path)
+            .unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let section = &contents[contents.find("[BACKTRACE]").unwrap()..];
+        // every frame up to this test is humantalk's own, so the backtrace starts in the test harness
+        let first = section.lines().nth(1).unwrap().trim_start();
+        assert!(first.starts_with("0: "), "{}", section);
+        assert!(!first.starts_with("0: humantalk::"), "{}", section);
+        assert!(!section.contains("Config::backtrace"));
+
+        config.set_capture_backtrace(false);
+        assert!(config.build_crash_report("crashed").backtrace.is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_trim_backtrace() {
+        let backtrace = "   0: std::backtrace::Backtrace::force_capture
+             at /rustc/library/std/src/backtrace.rs:312:9
+   1: humantalk::panic::<impl humantalk::Config>::install_panic_hook::{{closure}}
+   2: std::panicking::rust_panic_with_hook
+   3: core::panicking::panic_fmt
+   4: app::main
+             at ./src/main.rs:4:5
+   5: core::ops::function::FnOnce::call_once";
+
+        assert_eq!(
+            trim_backtrace(backtrace),
+            "   0: app::main
+             at ./src/main.rs:4:5
+   1: core::ops::function::FnOnce::call_once"
+        );
+    }
+
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_recent_log() {
+        use crate::{Severity, DEFAULT_RECENT_LOG_CAPACITY};
+
+        let dir = crate::test::scratch_dir("recent");
+        let path = dir.join(CRASH_REPORT_FILE);
+        let mut config = Config::default();
+        config.set_capture_backtrace(false);
+
+        for i in 0..150 {
+            config.write(Severity::Info, &format!("message {}", i));
+        }
+
+        let report = config.build_crash_report("crashed");
+        let mut file = File::create(&path).unwrap();
+        config
+            .write_crash_report(&report, &mut file, &path)
+            .unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let section: Vec<&str> = contents[contents.find("[RECENT LOG]\n").unwrap()..]
+            .lines()
+            .skip(1)
+            .take_while(|line| !line.is_empty())
+            .collect();
+
+        assert_eq!(section.len(), DEFAULT_RECENT_LOG_CAPACITY);
+        for (line, i) in section.iter().zip(50..) {
+            assert!(line.ends_with(&format!("[info] message {}", i)));
+        }
+        assert!(!contents.contains('\x1b'));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(not(feature = "max_level_error"))]
+    #[test]
+    fn test_json_crash_report() {
+        use crate::{Severity, VERSION};
+
+        let dir = crate::test::scratch_dir("json");
+        let mut config = Config::default();
+        config.set_crash_report_path(dir.join("crash_report.json"));
+        config.set_crash_report_format(CrashReportFormat::Json);
+        config.set_crash_report_retention(1);
+        config.set_capture_backtrace(false);
+        config.write(Severity::Warning, "about to \"crash\"\n\tbadly");
+
+        let paths = crash_twice(&config);
+        let contents = std::fs::read_to_string(&paths[0]).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+
+        let report: serde_json::Value = serde_json::from_str(contents.trim_end()).unwrap();
+        assert_eq!(report["message"], "second crash");
+        assert_eq!(report["exit_code"], FATAL_EXIT_CODE);
+        assert_eq!(report["bug_report"]["url"], "the appropriate place");
+        assert_eq!(report["machine"]["os"], std::env::consts::OS);
+        assert_eq!(report["machine"]["humantalk_version"], VERSION);
+        assert!(report["machine"]["rustc_version"].is_string());
+        assert!(report["machine"]["llvm_version"].is_string());
+        assert!(report["timestamp"].is_string());
+        assert!(report["backtrace"].is_null());
+        assert_eq!(report["recent_log"][0]["severity"], "warning");
+        assert_eq!(
+            report["recent_log"][0]["message"],
+            "about to \"crash\"\n\tbadly"
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_timestamped_path() {
+        assert_eq!(
+            timestamped_path(Path::new("logs/crash_report.log"), "20240502-103122"),
+            PathBuf::from("logs/crash_report-20240502-103122.log")
+        );
+        assert_eq!(
+            timestamped_path(Path::new("crash"), "20240502-103122"),
+            PathBuf::from("crash-20240502-103122")
+        );
+    }
+
+    #[cfg(not(feature = "max_level_error"))]
+    #[test]
+    fn test_build_crash_report() {
+        use crate::Severity;
+
+        let config = Config::custom(
+            Config::default().colors,
+            HowToBugReport::new("crashed".to_string(), "https://example.com".to_string()),
+        );
+        config.write(Severity::Warning, "about to crash");
+
+        let report = config.build_crash_report("boom");
+        assert_eq!(report.message, "boom");
+        assert_eq!(report.bug_report.url, "https://example.com");
+        assert_eq!(report.exit_code, FATAL_EXIT_CODE);
+        assert_eq!(report.machine_info.to_string(), config.machine_info());
+        assert_eq!(report.recent_log.last().unwrap().message, "about to crash");
+        assert!(report.app_info.is_none());
+        assert!(report.json().contains("\"app\":null"));
+        assert!(report.json().contains("\"context\":{}"));
+
+        let mut config = config;
+        config.set_app_info(crate::app_info!());
+        let report = config.build_crash_report("boom");
+        assert!(report
+            .plain(
+                std::path::Path::new("crash_report.log"),
+                &FatalText::default()
+            )
+            .contains(&format!(
+                "[PLATFORM INFO]\napp: humantalk {}\n",
+                env!("CARGO_PKG_VERSION")
+            )));
+        assert!(report.json().contains("\"app\":{\"name\":\"humantalk\""));
+
+        config.set_crash_context("request", "41");
+        config.set_crash_context("user", "ci");
+        config.set_crash_context("request", "42");
+        let report = config.build_crash_report("boom");
+        assert!(report
+            .plain(
+                std::path::Path::new("crash_report.log"),
+                &FatalText::default()
+            )
+            .contains("\n[CONTEXT]\nrequest: 42\nuser: ci\n"));
+        assert!(report
+            .json()
+            .contains("\"context\":{\"request\":\"42\",\"user\":\"ci\"}"));
+
+        config.set_capture_backtrace(false);
+        let text = config.crash_report_text("boom");
+        assert!(text.starts_with("[FATAL] boom\ncrashed. "));
+        assert!(text.contains("https://example.com"));
+        assert!(text.contains("\n[CONTEXT]\nrequest: 42\n"));
+        assert!(text.ends_with("[warning] about to crash\n"));
+        assert!(!text.contains(CRASH_HEADER));
+    }
+
+    #[test]
+    fn test_fatal_hooks() {
+        let dir = crate::test::scratch_dir("hooks");
+        let order = Arc::new(std::sync::Mutex::new(vec![]));
+        let mut config = Config::default();
+        config.set_crash_report_path(dir.join(CRASH_REPORT_FILE));
+        config.set_exit_behavior(ExitBehavior::Panic);
+
+        let first = Arc::clone(&order);
+        config.on_fatal(move |report| {
+            first
+                .lock()
+                .unwrap()
+                .push(format!("first: {}", report.message))
+        });
+        config.on_fatal(|_| panic!("hook failed"));
+        let third = Arc::clone(&order);
+        let path = dir.join(CRASH_REPORT_FILE);
+        config.on_fatal(move |_| {
+            third
+                .lock()
+                .unwrap()
+                .push(format!("report written: {}", path.exists()))
+        });
+
+        let _ = std::panic::catch_unwind(AssertUnwindSafe(|| config.fatal_error("boom")));
+        assert_eq!(
+            *order.lock().unwrap(),
+            vec![
+                "first: boom".to_string(),
+                "report written: true".to_string()
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_fatal_error_with_code() {
+        let dir = crate::test::scratch_dir("exit-code");
+        let codes = Arc::new(std::sync::Mutex::new(vec![]));
+        let mut config = Config::default();
+        config.set_crash_report_path(dir.join(CRASH_REPORT_FILE));
+        config.set_capture_backtrace(false);
+        config.set_exit_behavior(ExitBehavior::Panic);
+
+        let hook_codes = Arc::clone(&codes);
+        config.on_fatal(move |report| hook_codes.lock().unwrap().push(report.exit_code));
+
+        for code in [64, 0, -5, 300] {
+            let _ = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                config.fatal_error_with_code("boom", code)
+            }));
+        }
+        assert_eq!(*codes.lock().unwrap(), vec![64, 1, 1, 255]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[derive(Debug)]
+    struct Wrapped(&'static str, Option<std::io::Error>);
+
+    impl std::fmt::Display for Wrapped {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl Error for Wrapped {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            self.1.as_ref().map(|e| e as &(dyn Error + 'static))
+        }
+    }
+
+    #[test]
+    fn test_fatal_error_from() {
+        let dir = crate::test::scratch_dir("caused-by");
+        let mut config = Config::default();
+        config.set_crash_report_path(dir.join(CRASH_REPORT_FILE));
+        config.set_capture_backtrace(false);
+        config.set_exit_behavior(ExitBehavior::Panic);
+
+        let error = Wrapped(
+            "could not start",
+            Some(std::io::Error::other(Wrapped(
+                "could not read settings.toml",
+                None,
+            ))),
+        );
+        let _ = std::panic::catch_unwind(AssertUnwindSafe(|| config.fatal_error_from(&error)));
+
+        let report = std::fs::read_to_string(dir.join(CRASH_REPORT_FILE)).unwrap();
+        assert!(report.contains("[FATAL] could not start\n"));
+        assert!(report.contains(
+            "plaintext.\n[CAUSED BY]\n  could not read settings.toml\n[PLATFORM INFO]\n"
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_report_fatal() {
+        let dir = crate::test::scratch_dir("report_fatal");
+        let mut config = Config::default();
+        config.set_crash_report_path(dir.join(CRASH_REPORT_FILE));
+        config.set_capture_backtrace(false);
+
+        let path = config.report_fatal("worker crashed").unwrap();
+        assert_eq!(path, dir.join(CRASH_REPORT_FILE));
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("[FATAL] worker crashed"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_write_crash_log() {
+        let dir = crate::test::scratch_dir("no_crash_log");
+        let mut config = Config::default();
+        config.set_crash_report_path(dir.join(CRASH_REPORT_FILE));
+        config.set_capture_backtrace(false);
+        config.set_write_crash_log(false);
+
+        let report = config.build_crash_report("boom");
+        assert_eq!(config.report_crash(&report), FATAL_EXIT_CODE);
+        assert_eq!(
+            config.report_fatal("boom").map_err(|e| match e {
+                HumantalkError::Io(e) => e.kind(),
+                _ => unreachable!(),
+            }),
+            Err(std::io::ErrorKind::Unsupported)
+        );
+        assert!(!dir.join(CRASH_REPORT_FILE).exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_fatal_error_panics() {
+        let dir = crate::test::scratch_dir("panic");
+        let mut config = Config::default();
+        config.set_crash_report_path(dir.join(CRASH_REPORT_FILE));
+        config.set_exit_behavior(ExitBehavior::Panic);
+
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| config.fatal_error("boom")));
+        let payload = result.unwrap_err();
+        assert_eq!(
+            payload.downcast_ref::<String>().unwrap(),
+            "fatal error: boom"
+        );
+        assert!(std::fs::read_to_string(dir.join(CRASH_REPORT_FILE))
+            .unwrap()
+            .contains("[FATAL] boom"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,67 @@
+//! configuring humantalk from environment variables
+
+use crate::{Config, Severity};
+
+/// environment variable read by `Config::apply_env_level`, such as `HUMANTALK_LOG=warn`
+pub const LOG_ENV: &str = "HUMANTALK_LOG";
+
+/// fallback read by `Config::apply_env_level` when `LOG_ENV` is not set
+pub const RUST_LOG_ENV: &str = "RUST_LOG";
+
+impl Config {
+    /// a default config, with its level set from the environment by `apply_env_level`
+    pub fn from_env() -> Config {
+        let mut config = Config::default();
+        config.apply_env_level();
+        config
+    }
+
+    /// set `min_severity` from `HUMANTALK_LOG`, or `RUST_LOG` if that is not set. the value is a level name as
+    /// accepted by `Severity`'s `FromStr` impl, such as `warn` or `debug`. an invalid value leaves the level unchanged
+    /// and prints a warning
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::Config;
+    /// let mut config = Config::default();
+    ///
+    /// config.apply_env_level(); // `HUMANTALK_LOG=error ./app` only shows errors
+    /// ```
+    pub fn apply_env_level(&mut self) {
+        let variable = [LOG_ENV, RUST_LOG_ENV]
+            .into_iter()
+            .find_map(|name| std::env::var(name).ok().map(|value| (name, value)));
+
+        if let Some((name, value)) = variable {
+            self.apply_level(name, &value);
+        }
+    }
+
+    /// set `min_severity` from `value`, read from the environment variable `name`
+    fn apply_level(&mut self, name: &str, value: &str) {
+        match value.parse::<Severity>() {
+            Ok(severity) => self.set_min_severity(severity),
+            Err(e) => self.warning(&format!("ignoring {}: {}", name, e)),
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "max_level_error")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_apply_level() {
+        let mut config = Config::default();
+
+        config.apply_level(LOG_ENV, "WARN");
+        assert_eq!(config.min_severity, Severity::Warning);
+
+        config.apply_level(LOG_ENV, "loud");
+        assert_eq!(config.min_severity, Severity::Warning);
+        assert_eq!(config.count(&Severity::Warning), 1);
+
+        config.apply_level(RUST_LOG_ENV, "trace");
+        assert_eq!(config.min_severity, Severity::Trace);
+    }
+}
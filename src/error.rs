@@ -0,0 +1,99 @@
+//! one error type covering everything humantalk can fail with
+
+use crate::{ColorParseError, ParseSeverityError};
+
+/// any error returned by humantalk: parsing a severity or a color, and writing the crash report in
+/// `Config::report_fatal`. each of humantalk's own error types converts into it, so `?` works on them in a function
+/// returning `Result<_, HumantalkError>`. there is no case for reading the rustc version, as `build.rs` reads it at
+/// compile time and reports it as unknown if it cannot
+///
+/// # Examples
+/// ```rust
+/// use humantalk::{Config, HumantalkError, Severity};
+///
+/// fn configure(config: &mut Config, level: &str, color: &str) -> Result<(), HumantalkError> {
+///     config.set_min_severity(level.parse()?);
+///     config.set_color_hex(Severity::Info, color)?;
+///     Ok(())
+/// }
+///
+/// let mut config = Config::default();
+/// assert!(configure(&mut config, "warn", "#00afff").is_ok());
+/// assert!(matches!(configure(&mut config, "loud", "#00afff"), Err(HumantalkError::ParseSeverity(_))));
+/// ```
+#[derive(Debug)]
+pub enum HumantalkError {
+    /// reading or writing failed, such as writing a crash report
+    Io(std::io::Error),
+
+    /// a severity name could not be parsed
+    ParseSeverity(ParseSeverityError),
+
+    /// a color could not be parsed
+    ParseColor(ColorParseError),
+}
+
+impl std::fmt::Display for HumantalkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            HumantalkError::Io(e) => write!(f, "i/o error: {}", e),
+            HumantalkError::ParseSeverity(e) => e.fmt(f),
+            HumantalkError::ParseColor(e) => e.fmt(f),
+        }
+    }
+}
+
+/// the inner error's message is already part of `Display`, so `source` skips to whatever caused it, and error chains
+/// don't print it twice
+impl std::error::Error for HumantalkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HumantalkError::Io(e) => e.source(),
+            HumantalkError::ParseSeverity(e) => e.source(),
+            HumantalkError::ParseColor(e) => e.source(),
+        }
+    }
+}
+
+impl From<std::io::Error> for HumantalkError {
+    fn from(error: std::io::Error) -> Self {
+        HumantalkError::Io(error)
+    }
+}
+
+impl From<ParseSeverityError> for HumantalkError {
+    fn from(error: ParseSeverityError) -> Self {
+        HumantalkError::ParseSeverity(error)
+    }
+}
+
+impl From<ColorParseError> for HumantalkError {
+    fn from(error: ColorParseError) -> Self {
+        HumantalkError::ParseColor(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use super::*;
+    use crate::{parse_color, Severity};
+
+    #[test]
+    fn test_humantalk_error() {
+        let error = "loud".parse::<Severity>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unknown severity 'loud', expected error, warn, info, debug or trace"
+        );
+        assert!(error.source().is_none());
+
+        let error = parse_color("reddish").unwrap_err();
+        assert!(matches!(error, HumantalkError::ParseColor(_)));
+
+        let error = HumantalkError::from(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
+        assert!(error.to_string().starts_with("i/o error: "));
+        assert!(error.source().is_none());
+    }
+}
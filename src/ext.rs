@@ -0,0 +1,128 @@
+//! extension traits for ending a program, or warning, on `Err` and `None`
+
+use std::fmt::Display;
+
+use crate::Config;
+
+/// `or_fatal` and `or_warn` for `Result`s whose error can be displayed
+///
+/// # Examples
+/// ```rust,no_run
+/// use humantalk::{Config, ResultExt};
+/// let config = Config::default();
+///
+/// let settings = std::fs::read_to_string("settings.toml").or_fatal(&config, "loading settings failed");
+/// let cache = std::fs::read_to_string("cache.json").or_warn(&config, "no cache, starting fresh");
+/// ```
+pub trait ResultExt<T, E> {
+    /// return the value, or call `fatal_error` with `message` followed by the error
+    fn or_fatal(self, config: &Config, message: &str) -> T;
+
+    /// on `Err`, write `message` followed by the error as a warning. the result is returned unchanged
+    fn or_warn(self, config: &Config, message: &str) -> Self;
+}
+
+impl<T, E: Display> ResultExt<T, E> for Result<T, E> {
+    fn or_fatal(self, config: &Config, message: &str) -> T {
+        match self {
+            Ok(value) => value,
+            Err(e) => config.fatal_error(&format!("{}: {}", message, e)),
+        }
+    }
+
+    fn or_warn(self, config: &Config, message: &str) -> Self {
+        if let Err(e) = &self {
+            config.warning(&format!("{}: {}", message, e));
+        }
+
+        self
+    }
+}
+
+/// `or_fatal` and `or_warn` for `Option`s
+///
+/// # Examples
+/// ```rust,no_run
+/// use humantalk::{Config, OptionExt};
+/// let config = Config::default();
+///
+/// let home = std::env::var_os("HOME").or_fatal(&config, "HOME is not set");
+/// ```
+pub trait OptionExt<T> {
+    /// return the value, or call `fatal_error` with `message`
+    fn or_fatal(self, config: &Config, message: &str) -> T;
+
+    /// on `None`, write `message` as a warning. the option is returned unchanged
+    fn or_warn(self, config: &Config, message: &str) -> Self;
+}
+
+impl<T> OptionExt<T> for Option<T> {
+    fn or_fatal(self, config: &Config, message: &str) -> T {
+        match self {
+            Some(value) => value,
+            None => config.fatal_error(message),
+        }
+    }
+
+    fn or_warn(self, config: &Config, message: &str) -> Self {
+        if self.is_none() {
+            config.warning(message);
+        }
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use super::*;
+    use crate::{ExitBehavior, CRASH_REPORT_FILE};
+
+    #[test]
+    fn test_or_fatal() {
+        let dir = crate::test::scratch_dir("or-fatal");
+        let mut config = Config::default();
+        config.set_crash_report_path(dir.join(CRASH_REPORT_FILE));
+        config.set_capture_backtrace(false);
+        config.set_exit_behavior(ExitBehavior::Panic);
+
+        assert_eq!(Ok::<_, String>(1).or_fatal(&config, "unused"), 1);
+        assert_eq!(Some(2).or_fatal(&config, "unused"), 2);
+
+        let payload = catch_unwind(AssertUnwindSafe(|| {
+            "x".parse::<u8>().or_fatal(&config, "loading config failed")
+        }))
+        .unwrap_err();
+        assert_eq!(
+            payload.downcast_ref::<String>().unwrap(),
+            "fatal error: loading config failed: invalid digit found in string"
+        );
+
+        let payload = catch_unwind(AssertUnwindSafe(|| {
+            None::<u8>.or_fatal(&config, "missing value")
+        }))
+        .unwrap_err();
+        assert_eq!(
+            payload.downcast_ref::<String>().unwrap(),
+            "fatal error: missing value"
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(not(feature = "max_level_error"))]
+    #[test]
+    fn test_or_warn() {
+        let config = Config::default();
+
+        assert_eq!(
+            Err::<u8, _>("timed out").or_warn(&config, "retrying"),
+            Err("timed out")
+        );
+        assert_eq!(None::<u8>.or_warn(&config, "no cache"), None);
+        assert_eq!(Some(3).or_warn(&config, "unused"), Some(3));
+        assert_eq!(config.count(&crate::Severity::Warning), 2);
+    }
+}
@@ -0,0 +1,208 @@
+//! messages with structured key/value fields attached
+
+use std::fmt::Display;
+
+use crate::{format::logfmt, Config, Severity};
+
+/// a message with key/value fields, built with `Config::event` and written with `emit`. people see the fields dimmed
+/// after the message as `status=200 ms=41`, and the JSON and logfmt formats write them as fields of their own
+///
+/// # Examples
+/// ```rust
+/// use humantalk::{Config, Severity};
+/// let config = Config::default();
+///
+/// config
+///     .event(Severity::Info, "request finished")
+///     .field("status", 200)
+///     .field("ms", 41)
+///     .emit(); // [info] request finished status=200 ms=41
+/// ```
+#[must_use = "nothing is written until the event is emitted"]
+pub struct Event<'a> {
+    config: &'a Config,
+    severity: Severity,
+    message: String,
+    fields: Vec<(String, String)>,
+}
+
+impl Event<'_> {
+    /// attach `key=value` to the message, in the order fields are added
+    pub fn field(mut self, key: &str, value: impl Display) -> Self {
+        self.fields.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// write the message and its fields, filtered like `write`. events are never collapsed by `dedup_window`
+    pub fn emit(self) {
+        self.config.counts.record(&self.severity);
+
+        if !self.config.should_write(&self.severity) {
+            return;
+        }
+
+        self.config
+            .print(&self.severity, &self.message, None, &self.fields);
+    }
+}
+
+impl Config {
+    /// start a message of `severity` with key/value fields attached, see `Event`
+    pub fn event(&self, severity: Severity, message: &str) -> Event<'_> {
+        Event {
+            config: self,
+            severity,
+            message: message.to_string(),
+            fields: vec![],
+        }
+    }
+}
+
+impl Config {
+    /// a config whose messages all have `key=value` attached, for example one per request handler. fields accumulate
+    /// through chained calls, and setting a key again replaces its value. they are written like the fields of an
+    /// `Event`, before the event's own, which win if they share a key. they are also listed in crash reports.
+    ///
+    /// the new config is a clone, sharing sinks with this one, which is left unchanged
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::{Config, Severity};
+    /// let config = Config::default();
+    ///
+    /// let request = config.with_field("request_id", "7f3e2a").with_field("user", "ci");
+    /// request.info("handling request"); // [info] handling request request_id=7f3e2a user=ci
+    /// request.event(Severity::Info, "request finished").field("user", "admin").emit(); // ... request_id=7f3e2a user=admin
+    /// ```
+    pub fn with_field(&self, key: &str, value: impl Display) -> Config {
+        let mut config = self.clone();
+        let value = value.to_string();
+
+        match config
+            .fields
+            .iter_mut()
+            .find(|(existing, _)| existing == key)
+        {
+            Some((_, existing)) => *existing = value,
+            None => config.fields.push((key.to_string(), value)),
+        }
+
+        config
+    }
+
+    /// the config's own fields followed by a message's `fields`, leaving out any of the config's that the message sets
+    pub(crate) fn merged_fields(&self, fields: &[(String, String)]) -> Vec<(String, String)> {
+        self.fields
+            .iter()
+            .filter(|(key, _)| !fields.iter().any(|(other, _)| other == key))
+            .chain(fields)
+            .cloned()
+            .collect()
+    }
+}
+
+/// `message` with `fields` after it as logfmt pairs, as kept in the recent log and captured
+pub(crate) fn with_fields(message: &str, fields: &[(String, String)]) -> String {
+    match fields.is_empty() {
+        true => message.to_string(),
+        false => format!("{} {}", message, fields_text(fields)),
+    }
+}
+
+/// `fields` as logfmt pairs, like `status=200 path="/a b"`
+pub(crate) fn fields_text(fields: &[(String, String)]) -> String {
+    logfmt(
+        fields
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str())),
+    )
+}
+
+#[cfg(all(test, not(feature = "max_level_warn")))]
+mod test {
+    use super::*;
+    use crate::LogFormat;
+
+    #[test]
+    fn test_event_formats() {
+        let (mut config, buffer) = crate::test::buffered_config();
+
+        let emit = |config: &Config| {
+            config
+                .event(Severity::Info, "request \"finished\"")
+                .field("status", 200)
+                .field("path", "/a b")
+                .emit()
+        };
+
+        emit(&config);
+        config.set_log_format(LogFormat::Json);
+        emit(&config);
+        config.set_log_format(LogFormat::Logfmt);
+        emit(&config);
+
+        let contents = buffer.contents();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert!(lines[0].ends_with("[info] request \"finished\" status=200 path=\"/a b\""));
+
+        let json: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(json["level"], "info");
+        assert_eq!(json["message"], "request \"finished\"");
+        assert_eq!(json["status"], "200");
+        assert_eq!(json["path"], "/a b");
+        assert!(json["timestamp"].as_str().unwrap().ends_with('Z'));
+
+        assert!(lines[2].starts_with("time="));
+        assert!(lines[2]
+            .ends_with(" level=info msg=\"request \\\"finished\\\"\" status=200 path=\"/a b\""));
+
+        let report = config.build_crash_report("boom");
+        assert_eq!(
+            report.recent_log[0].message,
+            "request \"finished\" status=200 path=\"/a b\""
+        );
+    }
+
+    #[test]
+    fn test_with_field() {
+        let (config, buffer) = crate::test::buffered_config();
+
+        let request = config
+            .with_field("id", 7)
+            .with_field("user", "ci")
+            .with_field("id", 8);
+        assert_eq!(
+            request.fields,
+            vec![
+                ("id".to_string(), "8".to_string()),
+                ("user".to_string(), "ci".to_string())
+            ]
+        );
+        assert!(config.fields.is_empty());
+
+        request.info("started");
+        request
+            .event(Severity::Info, "finished")
+            .field("id", 9)
+            .field("ms", 41)
+            .emit();
+        config.info("idle");
+
+        let mut json = request.clone();
+        json.set_log_format(LogFormat::Json);
+        json.warning("slow");
+
+        let contents = buffer.contents();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert!(lines[0].ends_with("[info] started id=8 user=ci"));
+        assert!(lines[1].ends_with("[info] finished user=ci id=9 ms=41"));
+        assert!(lines[2].ends_with("[info] idle"));
+        assert!(lines[3].ends_with(",\"id\":\"8\",\"user\":\"ci\"}"));
+
+        let report = request.build_crash_report("boom");
+        assert!(report
+            .context
+            .contains(&("user".to_string(), "ci".to_string())));
+        assert_eq!(report.recent_log[1].message, "finished user=ci id=9 ms=41");
+    }
+}
@@ -0,0 +1,121 @@
+//! machine-readable line formats, for logs read by other programs rather than people
+
+use thetime::Time;
+
+use crate::{clock, json_string, Config, Severity, TIMESTAMP_FORMAT};
+
+/// how each line is written, set with `Config::set_log_format`
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum LogFormat {
+    /// styled lines for people, like `[info] request finished status=200`
+    #[default]
+    Human,
+
+    /// one JSON object per line, with `timestamp`, `level` and `message`, then `scope` and `location` if there are
+    /// any, then each field as a string
+    Json,
+
+    /// logfmt pairs, like `time=2024-05-02T10:31:22Z level=info msg="request finished" status=200`
+    Logfmt,
+}
+
+impl Config {
+    /// set how each line is written, `LogFormat::Human` by default. the structured formats are never colored, and
+    /// ignore the prefix template, groups and relative time
+    pub fn set_log_format(&mut self, format: LogFormat) {
+        self.log_format = format;
+    }
+
+    /// a message as a single line in the configured structured format
+    pub(crate) fn structured_line(
+        &self,
+        severity: &Severity,
+        message: &str,
+        location: Option<&str>,
+        fields: &[(String, String)],
+    ) -> String {
+        let (time_key, message_key) = match self.log_format {
+            LogFormat::Json => ("timestamp", "message"),
+            _ => ("time", "msg"),
+        };
+
+        let mut pairs = vec![
+            (time_key, clock::now().strftime(TIMESTAMP_FORMAT)),
+            ("level", severity.to_string()),
+            (message_key, message.to_string()),
+        ];
+        if let Some(scope) = &self.scope {
+            pairs.push(("scope", scope.clone()));
+        }
+        if let Some(location) = location.filter(|_| self.show_locations) {
+            pairs.push(("location", location.to_string()));
+        }
+        pairs.extend(
+            fields
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.clone())),
+        );
+
+        match self.log_format {
+            LogFormat::Json => {
+                let members = pairs
+                    .iter()
+                    .map(|(key, value)| format!("{}:{}", json_string(key), json_string(value)))
+                    .collect::<Vec<String>>();
+
+                format!("{{{}}}", members.join(","))
+            }
+            _ => logfmt(pairs.iter().map(|(key, value)| (*key, value.as_str()))),
+        }
+    }
+}
+
+/// `pairs` as space-separated `key=value` logfmt pairs. values are quoted if they are empty or hold spaces, quotes,
+/// `=` or control characters, and characters that would break a key are replaced with `_`
+pub(crate) fn logfmt<'a>(pairs: impl IntoIterator<Item = (&'a str, &'a str)>) -> String {
+    pairs
+        .into_iter()
+        .map(|(key, value)| {
+            let key: String = key
+                .chars()
+                .map(|c| match c {
+                    ' ' | '=' | '"' => '_',
+                    c if c.is_control() => '_',
+                    c => c,
+                })
+                .collect();
+
+            let quote = value.is_empty()
+                || value
+                    .chars()
+                    .any(|c| c == ' ' || c == '=' || c == '"' || c.is_control());
+            match quote {
+                true => format!("{}={}", key, json_string(value)),
+                false => format!("{}={}", key, value),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_logfmt() {
+        assert_eq!(
+            logfmt([("status", "200"), ("path", "/a b")]),
+            "status=200 path=\"/a b\""
+        );
+        assert_eq!(
+            logfmt([("bad key", "say \"hi\"\n"), ("empty", "")]),
+            "bad_key=\"say \\\"hi\\\"\\n\" empty=\"\""
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! a process-wide config, for logging without passing a `Config` around
+
+use std::sync::{PoisonError, RwLock};
+
+use crate::{Config, Logger};
+
+/// the config used by the free functions, set with `set_global` or created on first use
+static GLOBAL: RwLock<Option<Logger>> = RwLock::new(None);
+
+/// make `config` the global config, used by `humantalk::info` and friends and by the macros when they are called without
+/// a config. calling this again replaces the global config, after flushing the old one's buffered output. the global
+/// config is never dropped, so buffered output must be flushed with `global().flush()` before the program exits
+///
+/// # Examples
+/// ```rust
+/// use humantalk::{Config, Severity};
+/// let mut config = Config::default();
+/// config.set_min_severity(Severity::Info);
+/// humantalk::set_global(config);
+///
+/// humantalk::info("starting");
+/// humantalk::htwarning!("{} retries left", 3);
+/// ```
+pub fn set_global(config: Config) {
+    let old = GLOBAL
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .replace(Logger::new(config));
+
+    if let Some(old) = old {
+        old.flush();
+    }
+}
+
+/// the global config: the one given to `set_global`, or a default config if it was never called. it is returned as a
+/// `Logger`, a cheap handle to the global config, so nothing is copied on each call
+pub fn global() -> Logger {
+    if let Some(config) = GLOBAL
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+    {
+        return config.clone();
+    }
+
+    GLOBAL
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_or_insert_with(|| Logger::new(Config::default()))
+        .clone()
+}
+
+/// write an error with the global config
+pub fn error(message: &str) {
+    global().error(message);
+}
+
+/// write a warning with the global config. does nothing with the `max_level_error` feature
+pub fn warning(message: &str) {
+    global().warning(message);
+}
+
+/// write an info message with the global config. does nothing with the `max_level_warn` feature
+pub fn info(message: &str) {
+    global().info(message);
+}
+
+/// write a debug message with the global config. does nothing with the `strip-debug` or `max_level_info` features
+pub fn debug(message: &str) {
+    global().debug(message);
+}
+
+/// write a trace message with the global config. does nothing with the `strip-debug` or `max_level_debug` features
+pub fn trace(message: &str) {
+    global().trace(message);
+}
+
+/// error fatally with the global config, see `Config::fatal_error`
+pub fn fatal_error(message: &str) -> ! {
+    global().fatal_error(message)
+}
@@ -0,0 +1,79 @@
+//! indented groups for nesting related output under a heading
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use crate::Config;
+
+/// spaces added to the start of each line per level of nesting
+pub(crate) const INDENT_WIDTH: usize = 2;
+
+/// guard returned by `Config::group`. output is indented one level further until it is dropped
+///
+/// # Examples
+/// ```rust
+/// use humantalk::Config;
+/// let config = Config::default();
+///
+/// {
+///     let _build = config.group("building");
+///     config.info("compiling");
+///     {
+///         let _link = config.group("linking");
+///         config.info("resolving symbols");
+///     }
+/// }
+/// config.info("done"); // back at the top level
+/// ```
+#[must_use = "the group ends as soon as the guard is dropped"]
+pub struct Group {
+    indent: Arc<AtomicUsize>,
+}
+
+impl Drop for Group {
+    fn drop(&mut self) {
+        self.indent.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Config {
+    /// print `title` as an info line, then indent all further output under it until the returned guard is dropped.
+    /// groups nest, and clones of a config share the same indentation
+    pub fn group(&self, title: &str) -> Group {
+        self.info(title);
+        self.indent.fetch_add(1, Ordering::SeqCst);
+
+        Group {
+            indent: Arc::clone(&self.indent),
+        }
+    }
+
+    /// the whitespace placed at the start of each line for the current group nesting
+    pub(crate) fn indentation(&self) -> String {
+        " ".repeat(self.indent.load(Ordering::SeqCst) * INDENT_WIDTH)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_group_nesting() {
+        let config = Config::default();
+        assert_eq!(config.indentation(), "");
+
+        let outer = config.group("outer");
+        assert_eq!(config.indentation(), "  ");
+        {
+            let _inner = config.clone().group("inner");
+            assert_eq!(config.indentation(), "    ");
+        }
+        assert_eq!(config.indentation(), "  ");
+
+        drop(outer);
+        assert_eq!(config.indentation(), "");
+    }
+}
@@ -0,0 +1,70 @@
+//! errors followed by a suggestion for fixing them
+
+use console::style;
+
+use crate::{Config, LogFormat, Severity};
+
+impl Config {
+    /// write an error, then `hint` on the line below it like `  hint: did you mean --output?`, in cyan. the two lines
+    /// are written together, so no other message can come between them. with a structured `log_format` the hint is a
+    /// `hint` field of the error instead. like `write_colored`, it is never collapsed by `dedup_window`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// config.error_with_hint("unknown flag --ouput", "did you mean --output?");
+    /// ```
+    pub fn error_with_hint(&self, message: &str, hint: &str) {
+        if self.log_format != LogFormat::Human {
+            return self
+                .event(Severity::Error, message)
+                .field("hint", hint)
+                .emit();
+        }
+
+        self.counts.record(&Severity::Error);
+        if !self.should_write(&Severity::Error) {
+            return;
+        }
+
+        let Some(line) = self.prepare(&Severity::Error, message, None, None, &[]) else {
+            return;
+        };
+        let hint = style(format!("  hint: {}", hint))
+            .cyan()
+            .force_styling(true);
+
+        let mut output = self.lock_output();
+        let _ = output.write_line(&Severity::Error, &line, true);
+        let _ = output.write_line(
+            &Severity::Error,
+            &format!("{}{}", self.indentation(), hint),
+            self.buffers(&Severity::Error),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_error_with_hint() {
+        let (mut config, buffer) = crate::test::buffered_config();
+
+        config.error_with_hint("unknown flag --ouput", "did you mean --output?");
+        let contents = buffer.contents();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert!(lines[0].ends_with("[error] unknown flag --ouput"));
+        assert_eq!(lines[1], "  hint: did you mean --output?");
+        assert_eq!(config.count(&Severity::Error), 1);
+
+        config.set_log_format(LogFormat::Logfmt);
+        config.error_with_hint("missing config", "run init first");
+        assert!(buffer
+            .contents()
+            .ends_with("msg=\"missing config\" hint=\"run init first\"\n"));
+    }
+}
@@ -0,0 +1,128 @@
+//! the text humantalk writes around messages, for translating into other languages
+
+use crate::{Config, Severity};
+
+/// the text around a fatal error's message, in the console and crash report. the defaults are english
+///
+/// # Examples
+/// ```rust
+/// use humantalk::{Config, FatalText, Severity};
+/// let mut config = Config::default();
+///
+/// config.set_label(Severity::Error, "erreur");
+/// config.set_fatal_text(FatalText {
+///     label: "FATALE".to_string(),
+///     instructions: "Veuillez signaler le problème à {url}, avec une copie de ce message".to_string(),
+///     saved_to: "également enregistré dans {path}".to_string(),
+/// });
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct FatalText {
+    /// the tag before the message, `FATAL` by default
+    pub label: String,
+
+    /// the request to report the crash, following the bug report message. `{url}` is replaced with the bug report url
+    pub instructions: String,
+
+    /// added after the instructions when the crash report was saved. `{path}` is replaced with its path
+    pub saved_to: String,
+}
+
+impl Default for FatalText {
+    fn default() -> Self {
+        FatalText {
+            label: "FATAL".to_string(),
+            instructions:
+                "Please submit a report to {url}, along with a copy of this error message"
+                    .to_string(),
+            saved_to: "which can also be found in {path} as plaintext".to_string(),
+        }
+    }
+}
+
+impl FatalText {
+    /// the `[FATAL] message` line and the bug report line after it, with `url` and, if the report was saved, `path`
+    pub(crate) fn render(
+        &self,
+        message: &str,
+        bug_report: &str,
+        url: &str,
+        path: Option<&str>,
+    ) -> String {
+        let saved_to = match path {
+            Some(path) => format!(", {}", self.saved_to.replace("{path}", path)),
+            None => String::new(),
+        };
+
+        format!(
+            "[{}] {}\n{}. {}{}.\n",
+            self.label,
+            message,
+            bug_report,
+            self.instructions.replace("{url}", url),
+            saved_to
+        )
+    }
+}
+
+impl Config {
+    /// set the tag written for `severity`, such as `erreur` in place of `error`
+    pub fn set_label(&mut self, severity: Severity, label: &str) {
+        self.labels.insert(severity, label.to_string());
+    }
+
+    /// set the text written around fatal errors
+    pub fn set_fatal_text(&mut self, text: FatalText) {
+        self.fatal_text = text;
+    }
+
+    /// the tag written for `severity`: its label if one is set, otherwise its english name
+    pub(crate) fn label(&self, severity: &Severity) -> String {
+        match self.labels.get(severity) {
+            Some(label) => label.clone(),
+            None => severity.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_labels() {
+        let mut config = Config::default();
+        config.set_label(Severity::Error, "erreur");
+        assert_eq!(config.label(&Severity::Error), "erreur");
+        assert_eq!(config.label(&Severity::Warning), "warning");
+        assert_eq!(
+            config.prefix(&Severity::Error, "(10:31:22)"),
+            "(10:31:22) [erreur] "
+        );
+
+        config.set_prefix_template("{severity:upper}: ");
+        assert_eq!(config.prefix(&Severity::Error, "(10:31:22)"), "ERREUR: ");
+
+        let text = FatalText::default();
+        assert_eq!(
+            text.render("boom", "Oh no", "https://example.com", Some("crash_report.log")),
+            "[FATAL] boom\nOh no. Please submit a report to https://example.com, along with a copy of this error message, \
+which can also be found in crash_report.log as plaintext.\n"
+        );
+
+        let text = FatalText {
+            label: "FATALE".to_string(),
+            instructions: "Signalez-le à {url}".to_string(),
+            saved_to: "voir {path}".to_string(),
+        };
+        assert_eq!(
+            text.render("boum", "Oh non", "https://example.com", None),
+            "[FATALE] boum\nOh non. Signalez-le à https://example.com.\n"
+        );
+    }
+}
@@ -2,35 +2,148 @@
 //! Designed to make talking with the user easier
 //! [crates.io](https://crates.io/crates/humantalk)
 //! [github](https://github.com/werdl/humantalk)
- 
+
 /// console crate styling to customise the output of humantalk
-/// 
+///
 pub use console::{style, Color};
-use std::{collections::HashMap, io::Write};
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    path::PathBuf,
+    sync::{atomic::AtomicUsize, Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use clock::Instant;
 
-use thetime::{System, Time};
+/// file name used for crash reports when no path has been configured
+pub const CRASH_REPORT_FILE: &str = "crash_report.log";
 
-/// version of humantalk, manually updated each release
-pub const VERSION: &str = "0.1.1";
+/// format of the timestamps written into crash reports
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
 
-use rustc_version::version_meta;
+#[cfg(feature = "anyhow")]
+mod anyhow_ext;
+mod location;
+mod macros;
+mod output;
+
+mod app;
+mod attrs;
+mod banner;
+mod capture;
+mod clock;
+mod color;
+mod color_support;
+mod counts;
+mod crash;
+mod env;
+mod error;
+mod ext;
+mod fields;
+mod format;
+mod global;
+mod group;
+mod hint;
+mod labels;
+#[cfg(feature = "log")]
+mod log_ext;
+mod logger;
+mod machine;
+mod nonblocking;
+mod once;
+mod panic;
+mod progress;
+mod prompt;
+mod recent;
+mod scope;
+mod section;
+mod select;
+#[cfg(feature = "serde")]
+mod serde_color;
+mod source;
+mod spinner;
+mod steps;
+#[cfg(feature = "syslog")]
+mod syslog;
+mod table;
+mod template;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod theme;
+mod timestamp;
+#[cfg(feature = "tracing")]
+mod tracing_layer;
+mod verbosity;
+mod version;
+#[cfg(feature = "anyhow")]
+pub use anyhow_ext::run;
+pub use app::AppInfo;
+pub use attrs::StyleAttrs;
+pub use banner::{BannerOptions, BoxStyle};
+use capture::Captured;
+pub use color::{parse_color, ColorParseError};
+pub use color_support::ColorSupport;
+use counts::Counts;
+pub use counts::ERROR_EXIT_CODE;
+use crash::FatalHooks;
+pub use crash::{
+    CrashReport, CrashReportFormat, CrashReportMode, ExitBehavior, FatalStream, FATAL_EXIT_CODE,
+};
+pub use env::{LOG_ENV, RUST_LOG_ENV};
+pub use error::HumantalkError;
+pub use ext::{OptionExt, ResultExt};
+pub use fields::Event;
+pub use format::LogFormat;
+pub use global::{debug, error, fatal_error, global, info, set_global, trace, warning};
+pub use group::Group;
+pub use labels::FatalText;
+pub use logger::Logger;
+pub use machine::MachineInfo;
+pub use nonblocking::{
+    FullBehavior, NonblockingOptions, WorkerGuard, DEFAULT_NONBLOCKING_CAPACITY,
+};
+use output::Output;
+pub use output::Sink;
+pub use progress::ProgressHandle;
+use recent::RecentLog;
+pub use recent::{LogEntry, DEFAULT_RECENT_LOG_CAPACITY};
+pub use spinner::Spinner;
+pub use steps::StepTracker;
+#[cfg(feature = "syslog")]
+pub use syslog::Facility;
+pub use table::TableOptions;
+pub use template::DEFAULT_PREFIX_TEMPLATE;
+pub use theme::Theme;
+pub use timestamp::TimestampMode;
+#[cfg(feature = "tracing")]
+pub use tracing_layer::HumantalkLayer;
+pub use version::{version, version_info, VERSION};
 
 /// severity enum to denote severity of logging
-/// 
+///
 /// # Examples
 /// ```rust
 /// use humantalk::{Severity, Config};
 /// let config = Config::default();
-/// 
+///
 /// config.write(Severity::Error, "oh no!"); // non fatal
 /// config.write(Severity::Debug, "this will not trigger if compiled with --release");
 /// ```
 #[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum Severity {
     Error,
     Warning,
     Info,
     Debug,
+
+    /// finer detail than debug, hidden unless `min_severity` is lowered to it, for example with `set_verbosity(3)`
+    Trace,
 }
 
 impl std::fmt::Display for Severity {
@@ -40,13 +153,114 @@ impl std::fmt::Display for Severity {
             Severity::Warning => "warning",
             Severity::Info => "info",
             Severity::Debug => "debug",
+            Severity::Trace => "trace",
         };
         write!(f, "{}", s)
     }
 }
 
+impl std::str::FromStr for Severity {
+    type Err = HumantalkError;
+
+    /// parse a level name, ignoring case. `warn` is accepted for `Warning`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "error" => Ok(Severity::Error),
+            "warn" | "warning" => Ok(Severity::Warning),
+            "info" => Ok(Severity::Info),
+            "debug" => Ok(Severity::Debug),
+            "trace" => Ok(Severity::Trace),
+            _ => Err(HumantalkError::ParseSeverity(ParseSeverityError(
+                s.to_string(),
+            ))),
+        }
+    }
+}
+
+/// error returned when parsing an unknown severity name
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ParseSeverityError(String);
+
+impl std::fmt::Display for ParseSeverityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "unknown severity '{}', expected error, warn, info, debug or trace",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseSeverityError {}
+
+/// severities are ordered by how severe they are, so `Error > Warning > Info > Debug > Trace`. note that this is the reverse of
+/// the order they are declared in
+impl Ord for Severity {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl PartialOrd for Severity {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// the least severe level compiled in, `Trace` unless one of the `max_level_debug`, `max_level_info` (or
+/// `strip-debug`), `max_level_warn` or `max_level_error` features is on. less severe messages are never written, and
+/// the shorthand methods, macros and global functions for them do nothing, so their arguments are never formatted
+pub const STATIC_MIN_SEVERITY: Severity = if cfg!(feature = "max_level_error") {
+    Severity::Error
+} else if cfg!(feature = "max_level_warn") {
+    Severity::Warning
+} else if cfg!(feature = "max_level_info") {
+    Severity::Info
+} else if cfg!(feature = "max_level_debug") {
+    Severity::Debug
+} else {
+    Severity::Trace
+};
+
+impl Severity {
+    /// every severity, most severe first
+    pub(crate) const ALL: [Severity; 5] = [
+        Severity::Error,
+        Severity::Warning,
+        Severity::Info,
+        Severity::Debug,
+        Severity::Trace,
+    ];
+
+    /// whether this is at least as severe as `other`, so `Severity::Error.at_least(&Severity::Warning)` is true
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::Severity;
+    ///
+    /// assert!(Severity::Warning.at_least(&Severity::Info));
+    /// assert!(Severity::Warning.at_least(&Severity::Warning));
+    /// assert!(!Severity::Debug.at_least(&Severity::Info));
+    /// ```
+    pub fn at_least(&self, other: &Severity) -> bool {
+        self >= other
+    }
+
+    /// how severe this level is, higher being more severe
+    fn rank(&self) -> u8 {
+        match self {
+            Severity::Error => 4,
+            Severity::Warning => 3,
+            Severity::Info => 2,
+            Severity::Debug => 1,
+            Severity::Trace => 0,
+        }
+    }
+}
+
 /// Bug report struct, printed at fatal error
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HowToBugReport {
     /// the message to be displayed on crash
     pub message: String,
@@ -62,15 +276,269 @@ impl HowToBugReport {
     }
 }
 
-
 /// configuration struct for humantalk
+///
+/// settings are copied when a config is cloned, but its runtime state is shared between all of its clones, behind an
+/// `Arc`: the sinks, message counts, `dedup_window` repeats, `write_once` keys, the recent log and capture. so a clone
+/// passed into another thread writes to the same places and adds to the same counts as the original
+///
+/// with the `serde` feature, the settings can be serialized and deserialized. colors are written as a name (`"red"`) or
+/// a 256-color code, keyed by severity name. runtime state such as the recent log is not written, apart from its capacity
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Config {
     /// colors hashmap for each severity level
+    #[cfg_attr(feature = "serde", serde(with = "serde_color"))]
     pub colors: HashMap<Severity, Color>,
 
+    /// text attributes applied on top of each severity's color. errors are bold by default
+    #[cfg_attr(feature = "serde", serde(with = "serde_color::sorted"))]
+    pub styles: HashMap<Severity, StyleAttrs>,
+
+    /// tags written for each severity in place of their english names, see `set_label`
+    #[cfg_attr(feature = "serde", serde(default, with = "serde_color::sorted"))]
+    pub labels: HashMap<Severity, String>,
+
+    /// text written around fatal errors, see `set_fatal_text`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fatal_text: FatalText,
+
     /// the bug reporting struct
     pub bug_report: Option<HowToBugReport>,
+
+    /// the application's name and version, shown in crash reports
+    pub app_info: Option<AppInfo>,
+
+    /// key/value pairs listed in crash reports, see `set_crash_context`
+    pub crash_context: Vec<(String, String)>,
+
+    /// key/value fields attached to every message, see `with_field`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fields: Vec<(String, String)>,
+
+    /// where `fatal_error` writes the crash report. if unset, `crash_report.log` in the current directory is used
+    pub crash_report_path: Option<PathBuf>,
+
+    /// how existing crash reports are treated, appending to them by default
+    pub crash_report_mode: CrashReportMode,
+
+    /// the format crash reports are written in, plain text by default
+    pub crash_report_format: CrashReportFormat,
+
+    /// write crash reports to a file as well as printing them. on by default
+    pub write_crash_log: bool,
+
+    /// how many crash reports to keep, pruning the oldest. applies to the sections of an appended file and to the files
+    /// written per crash; the newest report is always kept. `None` keeps everything
+    pub crash_report_retention: Option<usize>,
+
+    /// include a backtrace in the crash report. on by default, unless `RUST_BACKTRACE=0` is set
+    pub capture_backtrace: bool,
+
+    /// what `fatal_error` does after reporting the crash, exiting with code `3` by default
+    pub exit_behavior: ExitBehavior,
+
+    /// the stream `fatal_error` prints its report to, stderr by default
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fatal_stream: FatalStream,
+
+    /// callbacks run by `fatal_error` before exiting, registered with `on_fatal`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    fatal_hooks: FatalHooks,
+
+    /// suppress everything below `Error` (for `--quiet`). `fatal_error` still prints
+    pub quiet: bool,
+
+    /// suppress every non-fatal message, including errors (for `--silent`). takes precedence over `quiet`
+    pub silent: bool,
+
+    /// buffer output instead of flushing after every message. buffered output is written by `flush`, before a fatal
+    /// error, when a message at least as severe as `flush_severity` is written, and when the last clone of the config
+    /// is dropped
+    pub buffered: bool,
+
+    /// while `buffered`, messages at least this severe are flushed straight away, along with everything buffered
+    /// before them. `Warning` by default, so warnings and errors show up in order with anything the program prints
+    /// itself, while info and below are batched. `None` buffers everything
+    #[cfg_attr(feature = "serde", serde(default = "default_flush_severity"))]
+    pub flush_severity: Option<Severity>,
+
+    /// if set, everything written to the sinks is also appended to this file, without colors. the file is created if
+    /// needed, and opened when the next message is written. see `set_tee_file`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tee_file: Option<PathBuf>,
+
+    /// the least severe level that is written, `Debug` (everything) by default
+    pub min_severity: Severity,
+
+    /// if set, identical messages repeated within this window are collapsed into a single `(repeated Nx)` line, written
+    /// when a different message arrives, on `flush` (which `fatal_error` calls), or when the last clone is dropped
+    pub dedup_window: Option<Duration>,
+
+    /// include the hostname and username in machine info and crash reports. on by default
+    pub include_identity: bool,
+
+    /// render the bug report url in `fatal_error` as a clickable OSC 8 hyperlink, on terminals that support it. on by
+    /// default
+    pub hyperlinks: bool,
+
+    /// show the source location given to `write_located` (and macros such as `error_at!`), dimmed, before the message.
+    /// on by default
+    pub show_locations: bool,
+
+    /// template each line's prefix is built from, `DEFAULT_PREFIX_TEMPLATE` unless set. see `set_prefix_template`
+    pub prefix_template: String,
+
+    /// pad the severity tag to the width of the longest label, so messages line up in a column. off by default
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub align_tags: bool,
+
+    /// how each line is written, styled for people by default. see `set_log_format`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub log_format: LogFormat,
+
+    /// the scope messages are prefixed with, like `network/tls`, set on the configs returned by `scoped`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub scope: Option<String>,
+
+    /// answer yes to every `confirm` without asking, see `set_assume_yes`
+    pub assume_yes: bool,
+
+    /// color questions are asked in. the warning color if unset
+    #[cfg_attr(feature = "serde", serde(default, with = "serde_color::option"))]
+    pub prompt_color: Option<Color>,
+
+    /// answers `prompt_validated` rejects before giving up, see `set_prompt_attempts`
+    pub prompt_attempts: usize,
+
+    /// what the timestamp before each message shows, none by default. see `set_timestamp_mode`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub timestamp_mode: TimestampMode,
+
+    /// when the config was created with `default` or `custom`. clones keep the original's start, so every clone measures
+    /// elapsed time from the same point
+    #[cfg_attr(feature = "serde", serde(skip, default = "Instant::now"))]
+    start: Instant,
+
+    /// last message seen, shared between clones so they collapse repeats together
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dedup: Arc<Mutex<Dedup>>,
+
+    /// when keys were last used with `write_once` and `write_throttled`, shared between clones
+    #[cfg_attr(feature = "serde", serde(skip))]
+    once: Arc<Mutex<once::OnceKeys>>,
+
+    /// how many messages have been logged at each severity, shared between clones
+    #[cfg_attr(feature = "serde", serde(skip))]
+    counts: Arc<Counts>,
+
+    /// how many `group`s deep the output currently is, shared between clones
+    #[cfg_attr(feature = "serde", serde(skip))]
+    indent: Arc<AtomicUsize>,
+
+    /// the most recent messages written, included in crash reports. shared between clones
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "recent_log_capacity",
+            with = "serde_color::recent_log_capacity"
+        )
+    )]
+    recent: Arc<Mutex<RecentLog>>,
+
+    /// messages recorded instead of printed, while capturing. shared between clones
+    #[cfg_attr(feature = "serde", serde(skip))]
+    capture: Arc<Mutex<Captured>>,
+
+    /// where messages are written, shared between clones so their output stays in order
+    #[cfg_attr(feature = "serde", serde(skip))]
+    output: Arc<Mutex<Output>>,
+
+    /// the system log, if `log_to_syslog` or `add_syslog` has connected to it. messages go here, instead of or as well
+    /// as `output`
+    #[cfg(feature = "syslog")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    syslog: Option<Arc<syslog::Syslog>>,
+}
+
+/// what makes two messages the same for `dedup_window`: their severity and text, and the scope and fields of the
+/// config that wrote them
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct DedupKey {
+    severity: Severity,
+    message: String,
+    scope: Option<String>,
+    fields: Vec<(String, String)>,
+}
+
+/// tracks the last message written, and how many times it has been repeated since it was printed
+#[derive(Debug, Default)]
+struct Dedup {
+    last: Option<(DedupKey, Instant)>,
+
+    /// how many times the last message has been repeated since it was printed, with a copy of the config that repeated
+    /// it to write the count with. the copy has its own dedup state, so it can write while this one is locked
+    repeats: Option<(usize, Config)>,
+}
+
+/// the count of a message's repeats held back by `dedup_window`, and the config to write it with
+struct Repeats {
+    writer: Config,
+    severity: Severity,
+    summary: String,
+}
+
+impl Repeats {
+    fn write(&self) {
+        self.writer.print(&self.severity, &self.summary, None, &[]);
+    }
+}
+
+impl Drop for Dedup {
+    fn drop(&mut self) {
+        if let Some(repeats) = self.take_repeats() {
+            repeats.write();
+        }
+    }
+}
+
+impl Dedup {
+    /// record a message seen at `now`, returning the count of the previous message's repeats if it should be written
+    /// first, and whether the message itself should be written. `writer` makes the config a repeat's count is written
+    /// with, and is only called for the first repeat
+    fn observe(
+        &mut self,
+        key: DedupKey,
+        window: Duration,
+        now: Instant,
+        writer: impl FnOnce() -> Config,
+    ) -> (Option<Repeats>, bool) {
+        if let Some((last, since)) = &self.last {
+            if *last == key && now.duration_since(*since) < window {
+                match &mut self.repeats {
+                    Some((repeats, _)) => *repeats += 1,
+                    None => self.repeats = Some((1, writer())),
+                }
+                return (None, false);
+            }
+        }
+
+        let repeats = self.take_repeats();
+        self.last = Some((key, now));
+        (repeats, true)
+    }
+
+    /// the count of any repeats of the last message that have not yet been written
+    fn take_repeats(&mut self) -> Option<Repeats> {
+        let (repeats, writer) = self.repeats.take()?;
+        let (last, _) = self.last.as_ref()?;
+
+        Some(Repeats {
+            writer,
+            severity: last.severity.clone(),
+            summary: format!("{} (repeated {}x)", last.message, repeats),
+        })
+    }
 }
 
 trait ColorToColor256 {
@@ -93,24 +561,75 @@ impl ColorToColor256 for Color {
     }
 }
 
+impl Default for Config {
+    /// the same as the inherent `Config::default`, for `#[derive(Default)]` and `T: Default` bounds
+    fn default() -> Config {
+        Config::default()
+    }
+}
+
+/// the default `flush_severity`
+fn default_flush_severity() -> Option<Severity> {
+    Some(Severity::Warning)
+}
+
 impl Config {
     /// create a new configuration, with default colors and no bug report (auto-filled with default values on use)
+    #[allow(clippy::should_implement_trait)]
     pub fn default() -> Config {
-        let mut colors = HashMap::new();
-        colors.insert(Severity::Error, Color::Red);
-        colors.insert(Severity::Warning, Color::Yellow);
-        colors.insert(Severity::Info, Color::Green);
-        colors.insert(Severity::Debug, Color::Blue);
         Config {
-            colors,
+            colors: Theme::Default.colors(),
+            styles: Theme::Default.styles(),
+            labels: HashMap::new(),
+            fatal_text: FatalText::default(),
             bug_report: None,
+            app_info: None,
+            crash_context: vec![],
+            fields: vec![],
+            crash_report_path: None,
+            crash_report_mode: CrashReportMode::Append,
+            crash_report_format: CrashReportFormat::Plain,
+            write_crash_log: true,
+            crash_report_retention: None,
+            capture_backtrace: std::env::var("RUST_BACKTRACE").map_or(true, |value| value != "0"),
+            exit_behavior: ExitBehavior::Exit,
+            fatal_stream: FatalStream::Stderr,
+            fatal_hooks: FatalHooks::default(),
+            quiet: false,
+            silent: false,
+            buffered: false,
+            flush_severity: default_flush_severity(),
+            tee_file: None,
+            min_severity: Severity::Debug,
+            dedup_window: None,
+            include_identity: true,
+            hyperlinks: true,
+            show_locations: true,
+            prefix_template: DEFAULT_PREFIX_TEMPLATE.to_string(),
+            align_tags: false,
+            log_format: LogFormat::Human,
+            scope: None,
+            assume_yes: false,
+            prompt_color: None,
+            prompt_attempts: 3,
+            timestamp_mode: TimestampMode::None,
+            start: Instant::now(),
+            dedup: Arc::new(Mutex::new(Dedup::default())),
+            once: Arc::new(Mutex::new(once::OnceKeys::new())),
+            counts: Arc::new(Counts::default()),
+            indent: Arc::new(AtomicUsize::new(0)),
+            recent: Arc::new(Mutex::new(RecentLog::new(DEFAULT_RECENT_LOG_CAPACITY))),
+            capture: Arc::new(Mutex::new(None)),
+            output: Arc::new(Mutex::new(Output::default())),
+            #[cfg(feature = "syslog")]
+            syslog: None,
         }
     }
 
     /// create a custom config, with your own colors and bug report. If you just want custom bug report, just use this code (inverse for colors):
     /// ```
     /// use humantalk::{Config, Severity, HowToBugReport};
-    /// 
+    ///
     /// let my_config = Config::custom(
     ///     Config::default().colors,
     ///     HowToBugReport::new(
@@ -119,13 +638,11 @@ impl Config {
     ///     )
     /// );
     /// ```
-    pub fn custom(
-        colors: HashMap<Severity, Color>,
-        bug_report: HowToBugReport,
-    ) -> Config {
+    pub fn custom(colors: HashMap<Severity, Color>, bug_report: HowToBugReport) -> Config {
         Config {
             colors,
             bug_report: Some(bug_report),
+            ..Config::default()
         }
     }
 
@@ -143,139 +660,1164 @@ impl Config {
         self.colors.insert(severity, color);
     }
 
+    /// collapse identical messages repeated within `window` into a single `(repeated Nx)` line. consecutive identical
+    /// messages within `window` are held back, and counted in a line like `disk full (repeated 1473x)` once a different
+    /// message is written, the same message is written after the window has passed, or the config is flushed or dropped.
+    /// messages only count as identical if they come from the same scope with the same fields, and the count is written
+    /// with the scope and fields of the config that repeated them
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::time::Duration;
+    /// use humantalk::Config;
+    /// let mut config = Config::default();
+    ///
+    /// config.set_dedup_window(Duration::from_secs(5));
+    /// for _ in 0..1000 {
+    ///     config.warning("connection refused, retrying"); // written once
+    /// }
+    /// config.info("connected"); // after `connection refused, retrying (repeated 999x)`
+    /// ```
+    pub fn set_dedup_window(&mut self, window: Duration) {
+        self.dedup_window = Some(window);
+    }
+
+    /// same as `set_dedup_window`
+    pub fn set_dedup(&mut self, window: Duration) {
+        self.set_dedup_window(window);
+    }
+
+    /// set whether the hostname and username are included in machine info and crash reports. turn this off for
+    /// privacy-sensitive programs
+    pub fn set_include_identity(&mut self, include_identity: bool) {
+        self.include_identity = include_identity;
+    }
+
+    /// set whether `fatal_error` renders the bug report url as a clickable hyperlink, when the terminal supports it
+    pub fn set_hyperlinks(&mut self, hyperlinks: bool) {
+        self.hyperlinks = hyperlinks;
+    }
+
+    /// `hyperlink`, unless hyperlinks have been turned off with `set_hyperlinks`
+    fn link(&self, url: &str, label: &str) -> String {
+        if self.hyperlinks {
+            hyperlink(url, label)
+        } else {
+            render_hyperlink(url, label, false)
+        }
+    }
+
+    /// prefix messages with the time elapsed since the config was created, like `[+0.342s]`, instead of the time of day.
+    /// shorthand for `set_timestamp_mode` with `TimestampMode::Elapsed`, or `TimestampMode::Wallclock` if `false`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::Config;
+    /// let mut config = Config::default(); // relative time is measured from here
+    ///
+    /// config.set_relative_time(true);
+    /// config.info("started"); // [+0.000s] [info] started
+    /// ```
+    pub fn set_relative_time(&mut self, relative_time: bool) {
+        self.timestamp_mode = match relative_time {
+            true => TimestampMode::Elapsed,
+            false => TimestampMode::Wallclock,
+        };
+    }
+
+    /// set how many recent messages are kept for the `[RECENT LOG]` section of crash reports. `0` turns it off
+    pub fn set_recent_log_capacity(&mut self, capacity: usize) {
+        self.recent
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .set_capacity(capacity);
+    }
+
+    /// set whether output is buffered, rather than flushed after every message. see `flush_severity` for the messages
+    /// that are flushed anyway. anything printed outside humantalk, such as with `println!`, can come out before
+    /// buffered messages that were written earlier, so call `flush` first if the order matters
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::Config;
+    /// let mut config = Config::default();
+    /// config.set_buffered(true);
+    ///
+    /// for i in 0..1000 {
+    ///     config.info(&format!("row {}", i)); // batched
+    /// }
+    /// config.warning("3 rows skipped"); // flushed straight away, after the rows
+    /// ```
+    pub fn set_buffered(&mut self, buffered: bool) {
+        self.buffered = buffered;
+    }
+
+    /// also append a plain copy of everything written to the file at `path`, so the terminal gets colors and the file
+    /// keeps a log. unlike `add_sink` with `Sink::file`, this is a setting, so it can come from a config file. nothing
+    /// is mirrored if the file can't be opened. the open file is shared between clones, so set it before cloning
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use humantalk::Config;
+    /// let mut config = Config::default();
+    ///
+    /// config.set_tee_file("app.log".into());
+    /// config.info("in color on the terminal, plain in app.log");
+    /// ```
+    pub fn set_tee_file(&mut self, path: PathBuf) {
+        self.tee_file = Some(path);
+    }
+
+    /// set the least severe level that is flushed straight away while output is buffered, or `None` to buffer
+    /// everything until `flush`
+    pub fn set_flush_severity(&mut self, severity: Option<Severity>) {
+        self.flush_severity = severity;
+    }
+
+    /// whether a message of `severity` is left in the buffer, rather than flushed straight away
+    pub(crate) fn buffers(&self, severity: &Severity) -> bool {
+        match &self.flush_severity {
+            Some(flush_severity) => self.buffered && !severity.at_least(flush_severity),
+            None => self.buffered,
+        }
+    }
+
+    /// set quiet mode, which suppresses everything below `Error`
+    pub fn set_quiet(&mut self, quiet: bool) {
+        self.quiet = quiet;
+    }
+
+    /// set silent mode, which suppresses all non-fatal output
+    pub fn set_silent(&mut self, silent: bool) {
+        self.silent = silent;
+    }
+
+    /// set the least severe level that is written
+    pub fn set_min_severity(&mut self, severity: Severity) {
+        self.min_severity = severity;
+    }
+
+    /// whether a message of the given severity should be printed. `silent` is checked first and hides everything,
+    /// then `quiet` hides everything below `Error`, then `min_severity` is applied, then debug and trace messages are hidden in
+    /// release builds, and anything below `STATIC_MIN_SEVERITY`. so `quiet` wins over a lower `min_severity`, and a higher `min_severity` still applies when quiet
+    fn should_write(&self, severity: &Severity) -> bool {
+        if self.silent {
+            return false;
+        }
+
+        if self.quiet && *severity != Severity::Error {
+            return false;
+        }
+
+        if !severity.at_least(&self.min_severity) {
+            return false;
+        }
+
+        #[cfg(not(debug_assertions))]
+        if matches!(severity, Severity::Debug | Severity::Trace) {
+            return false;
+        }
+
+        if !severity.at_least(&STATIC_MIN_SEVERITY) {
+            return false;
+        }
+
+        true
+    }
+
     /// write a logging message to stdout. if the binary has been compiled with --release, it will not print debug assertions.
+    /// nothing is printed in silent mode, and only errors are printed in quiet mode. the lines of a multi-line message
+    /// after the first are indented to line up under it
     pub fn write(&self, severity: Severity, message: &str) {
-        #[cfg(not(debug_assertions))]
-        if severity == Severity::Debug {
+        self.counts.record(&severity);
+
+        if !self.should_write(&severity) {
             return;
         }
 
-        let color = self.get_color(&severity);
-        let styled = style(format!("({}) [{}] {}", System::now().strftime("%H:%m:%S%p"), severity, message)).color256(color.to_color256());
-        println!("{}", styled);
+        let write = match self.dedup_window {
+            Some(window) => {
+                let (repeats, write) = self.observe(&severity, message, window);
+                if let Some(repeats) = repeats {
+                    repeats.write();
+                }
+                write
+            }
+            None => true,
+        };
+
+        if write {
+            self.print(&severity, message, None, &[]);
+        }
+    }
+
+    /// write several messages of the same severity, filtered like `write`. the sinks are locked and flushed once for
+    /// the whole batch rather than once per message, see `write_batch`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::{Config, Severity};
+    /// let config = Config::default();
+    ///
+    /// config.write_all(Severity::Info, &["compiled 12 files", "linked 3 binaries", "copied assets"]);
+    /// ```
+    pub fn write_all(&self, severity: Severity, messages: &[&str]) {
+        let entries: Vec<(Severity, &str)> = messages
+            .iter()
+            .map(|message| (severity.clone(), *message))
+            .collect();
+        self.write_batch(&entries);
+    }
+
+    /// write several messages, each filtered like `write`, as one block. the sinks stay locked for the whole batch, so
+    /// messages written from other threads at the same time come before or after it, never in the middle. a single
+    /// message from `write` is always written whole in the same way
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::{Config, Severity};
+    /// let config = Config::default();
+    ///
+    /// config.write_batch(&[
+    ///     (Severity::Error, "3 tests failed"),
+    ///     (Severity::Info, "  parse::nested"),
+    ///     (Severity::Info, "  render::unicode"),
+    /// ]);
+    /// ```
+    pub fn write_batch(&self, entries: &[(Severity, &str)]) {
+        let mut lines = vec![];
+        for (severity, message) in entries {
+            self.counts.record(severity);
+
+            if !self.should_write(severity) {
+                continue;
+            }
+
+            let (repeats, write) = match self.dedup_window {
+                Some(window) => self.observe(severity, message, window),
+                None => (None, true),
+            };
+
+            if let Some(repeats) = repeats {
+                let line =
+                    repeats
+                        .writer
+                        .prepare(&repeats.severity, &repeats.summary, None, None, &[]);
+                lines.extend(line.map(|line| (repeats.severity, line)));
+            }
+            if write {
+                lines.extend(
+                    self.prepare(severity, message, None, None, &[])
+                        .map(|line| (severity.clone(), line)),
+                );
+            }
+        }
+
+        let mut output = self.lock_output();
+        for (severity, line) in &lines {
+            let _ = output.write_line(severity, line, true);
+        }
+
+        if lines.iter().any(|(severity, _)| !self.buffers(severity)) {
+            let _ = output.flush();
+        }
     }
-    
-    /// shorthand for `config.write(Severity::Debug, ...)`
+
+    /// write a logging message like `write`, but in `color` rather than the severity's color, for one-off emphasis.
+    /// the config itself is unchanged. colored messages are never collapsed by `dedup_window`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::{Color, Config, Severity};
+    /// let config = Config::default();
+    ///
+    /// config.write_colored(Severity::Info, "all 212 tests passed", Color::Cyan);
+    /// ```
+    pub fn write_colored(&self, severity: Severity, message: &str, color: Color) {
+        self.counts.record(&severity);
+
+        if !self.should_write(&severity) {
+            return;
+        }
+
+        if let Some(line) = self.prepare(&severity, message, None, Some(color), &[]) {
+            let _ = self
+                .lock_output()
+                .write_line(&severity, &line, self.buffers(&severity));
+        }
+    }
+
+    /// write a logging message to `writer` rather than the config's sinks, flushing it straight away. unlike `write`,
+    /// errors are returned, so a closed pipe (`io::ErrorKind::BrokenPipe`) can be told apart from success. filtering
+    /// applies as usual, and the message is styled with the colors `color` can show, so `ColorSupport::None` for a
+    /// file or pipe
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::{ColorSupport, Config, Severity};
+    /// let config = Config::default();
+    ///
+    /// let mut stderr = std::io::stderr();
+    /// if let Err(e) = config.write_to(&mut stderr, ColorSupport::None, Severity::Warning, "disk almost full") {
+    ///     if e.kind() == std::io::ErrorKind::BrokenPipe {
+    ///         std::process::exit(0);
+    ///     }
+    /// }
+    /// ```
+    pub fn write_to(
+        &self,
+        writer: &mut impl Write,
+        color: ColorSupport,
+        severity: Severity,
+        message: &str,
+    ) -> io::Result<()> {
+        self.counts.record(&severity);
+
+        if !self.should_write(&severity) {
+            return Ok(());
+        }
+
+        let line = self.line(&severity, message, None);
+        writeln!(writer, "{}", color.convert(&line))?;
+        writer.flush()
+    }
+
+    /// style and print a single message, without any filtering. `location` is shown dimmed before the message, if
+    /// locations are shown, and `fields` after it
+    pub(crate) fn print(
+        &self,
+        severity: &Severity,
+        message: &str,
+        location: Option<&str>,
+        fields: &[(String, String)],
+    ) {
+        if let Some(line) = self.prepare(severity, message, location, None, fields) {
+            let _ = self
+                .lock_output()
+                .write_line(severity, &line, self.buffers(severity));
+        }
+    }
+
+    /// record a message in the recent log and hand it to capture or syslog if either is on. returns the styled line to
+    /// write to the sinks, in `color` if given rather than the severity's color, unless capture or syslog replaces them
+    fn prepare(
+        &self,
+        severity: &Severity,
+        message: &str,
+        location: Option<&str>,
+        color: Option<Color>,
+        fields: &[(String, String)],
+    ) -> Option<String> {
+        let fields = self.merged_fields(fields);
+        let fields = fields.as_slice();
+        let scoped = fields::with_fields(&self.scoped_message(message), fields);
+        self.recent
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(severity, &scoped);
+
+        if self.try_capture(severity, &scoped) {
+            return None;
+        }
+
+        #[cfg(feature = "syslog")]
+        if let Some(syslog) = &self.syslog {
+            let _ = syslog.send(severity, &scoped);
+            if syslog.exclusive {
+                return None;
+            }
+        }
+
+        let color = color.unwrap_or_else(|| self.get_color(severity));
+        Some(self.line_colored(severity, message, location, color, fields))
+    }
+
+    /// a message styled as a single line, with its prefix and `location`
+    fn line(&self, severity: &Severity, message: &str, location: Option<&str>) -> String {
+        self.line_colored(severity, message, location, self.get_color(severity), &[])
+    }
+
+    /// a message styled as a single line like `line`, in `color`, with `fields` dimmed after it. in the structured
+    /// formats, the line is not styled at all
+    fn line_colored(
+        &self,
+        severity: &Severity,
+        message: &str,
+        location: Option<&str>,
+        color: Color,
+        fields: &[(String, String)],
+    ) -> String {
+        if self.log_format != LogFormat::Human {
+            return self.structured_line(severity, message, location, fields);
+        }
+
+        let time = self.timestamp();
+        let location = match location {
+            Some(location) if self.show_locations => {
+                format!(
+                    "{} ",
+                    style(format!("[{}]", location)).dim().force_styling(true)
+                )
+            }
+            _ => String::new(),
+        };
+        // the scope goes before the message, unless the prefix template places it
+        let message = match self.prefix_template.contains("{scope}") {
+            true => message.into(),
+            false => self.scoped_message(message),
+        };
+        let color = color.to_color256();
+        let attrs = self.get_style(severity);
+        // trailing whitespace is left unstyled, so underlines stop at the prefix's last character
+        let prefix = format!("{}{}", self.indentation(), self.prefix(severity, &time));
+        let styled = prefix.trim_end();
+
+        // continuation lines of a multi-line message line up under its first line
+        let continuation = format!(
+            "\n{}",
+            " ".repeat(
+                console::measure_text_width(&prefix) + console::measure_text_width(&location)
+            )
+        );
+        let body = message
+            .split('\n')
+            .map(|line| {
+                attrs
+                    .apply(style(line).color256(color).force_styling(true))
+                    .to_string()
+            })
+            .collect::<Vec<String>>()
+            .join(&continuation);
+        let fields = match fields.is_empty() {
+            true => String::new(),
+            false => format!(
+                " {}",
+                style(fields::fields_text(fields)).dim().force_styling(true)
+            ),
+        };
+
+        format!(
+            "{}{}{}{}{}",
+            attrs.apply(style(styled).color256(color).force_styling(true)),
+            &prefix[styled.len()..],
+            location,
+            body,
+            fields
+        )
+    }
+
+    /// the sinks, with the tee file opened or closed to match `tee_file`
+    pub(crate) fn lock_output(&self) -> MutexGuard<'_, Output> {
+        let mut output = self.output.lock().unwrap_or_else(PoisonError::into_inner);
+        output.set_tee(self.tee_file.as_deref());
+        output
+    }
+
+    /// also write messages to `sink`, alongside stdout and any other sinks. sinks are shared between clones
+    pub fn add_sink(&self, sink: Sink) {
+        self.lock_output().add(sink);
+    }
+
+    /// write out any buffered output, after the count of any repeats held back by `dedup_window`
+    pub fn flush(&self) {
+        self.flush_repeats();
+        let _ = self.lock_output().flush();
+    }
+
+    /// pass a message to `dedup_window`'s state, returning the count of held back repeats to write first, if any, and
+    /// whether to write the message
+    fn observe(
+        &self,
+        severity: &Severity,
+        message: &str,
+        window: Duration,
+    ) -> (Option<Repeats>, bool) {
+        let key = DedupKey {
+            severity: severity.clone(),
+            message: message.to_string(),
+            scope: self.scope.clone(),
+            fields: self.merged_fields(&[]),
+        };
+
+        self.dedup
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .observe(key, window, Instant::now(), || {
+                let mut writer = self.clone();
+                writer.dedup = Arc::new(Mutex::new(Dedup::default()));
+                writer.dedup_window = None;
+                writer
+            })
+    }
+
+    /// write the `(repeated Nx)` line for repeats of the last message that `dedup_window` is holding back, if any
+    fn flush_repeats(&self) {
+        let repeats = self
+            .dedup
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take_repeats();
+
+        if let Some(repeats) = repeats {
+            repeats.write();
+        }
+    }
+
+    /// write a formatted message, as built by `format_args!`. nothing is formatted if the message would be filtered out,
+    /// which makes this cheaper than `write(severity, &format!(...))`. usually called through `htwrite!`, `htinfo!` etc.
+    pub fn write_fmt(&self, severity: Severity, args: std::fmt::Arguments) {
+        if !self.should_write(&severity) {
+            self.counts.record(&severity);
+            return;
+        }
+
+        match args.as_str() {
+            Some(message) => self.write(severity, message),
+            None => self.write(severity, &args.to_string()),
+        }
+    }
+
+    /// shorthand for `config.write(Severity::Debug, ...)`. does nothing with the `strip-debug` or `max_level_info`
+    /// features
     pub fn debug(&self, message: &str) {
+        #[cfg(not(feature = "max_level_info"))]
         self.write(Severity::Debug, message);
+        #[cfg(feature = "max_level_info")]
+        let _ = message;
     }
 
-    /// shorthand for `config.write(Severity::Info, ...)`
+    /// shorthand for `config.write(Severity::Trace, ...)`. does nothing with the `strip-debug` or `max_level_debug`
+    /// features
+    pub fn trace(&self, message: &str) {
+        #[cfg(not(feature = "max_level_debug"))]
+        self.write(Severity::Trace, message);
+        #[cfg(feature = "max_level_debug")]
+        let _ = message;
+    }
+
+    /// shorthand for `config.write(Severity::Info, ...)`. does nothing with the `max_level_warn` feature
     pub fn info(&self, message: &str) {
+        #[cfg(not(feature = "max_level_warn"))]
         self.write(Severity::Info, message);
+        #[cfg(feature = "max_level_warn")]
+        let _ = message;
     }
 
     /// shorthand for `config.write(Severity::Error, ...)`
     pub fn error(&self, message: &str) {
         self.write(Severity::Error, message);
-    } 
+    }
 
-    /// shorthand for `config.write(Severity::Warning, ...)`
+    /// shorthand for `config.write(Severity::Warning, ...)`. does nothing with the `max_level_error` feature
     pub fn warning(&self, message: &str) {
+        #[cfg(not(feature = "max_level_error"))]
         self.write(Severity::Warning, message);
+        #[cfg(feature = "max_level_error")]
+        let _ = message;
     }
 
-    /// get machine info represented as a string. Contains info including OS family, os, arch, rust version, llvm version and humantalk version
+    /// get machine info represented as a string. Contains info including OS family, os, arch, environment, CPU count,
+    /// memory, rust version, llvm version and humantalk version
     pub fn machine_info(&self) -> String {
-        let arch = std::env::consts::ARCH.to_string();
-        let os = std::env::consts::OS.to_string();
-        let family = std::env::consts::FAMILY.to_string();
+        self.machine_info_struct().to_string()
+    }
+
+    /// get machine info as a `MachineInfo`, for using its fields separately. it is gathered once, on first use
+    pub fn machine_info_struct(&self) -> MachineInfo {
+        let info = MachineInfo::get().clone();
+
+        if self.include_identity {
+            info
+        } else {
+            info.without_identity()
+        }
+    }
+}
 
-        let rustc_info = version_meta().unwrap_or_else(|e: rustc_version::Error| {
-            println!("humantalk has failed (with message {e}). Please submit an issue at github.com/werdl/humantalk");
+/// quote and escape a string as a JSON string literal
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
 
-            std::process::exit(-1)
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
         }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+/// wrap `label` in an OSC 8 hyperlink to `url`, so terminals that support it make it clickable. falls back to plain text
+/// when colors are disabled (`NO_COLOR`, `TERM=dumb`, or stdout is not a terminal) or `url` is not a link
+pub fn hyperlink(url: &str, label: &str) -> String {
+    render_hyperlink(url, label, console::colors_enabled() && url.contains("://"))
+}
+
+fn render_hyperlink(url: &str, label: &str, enabled: bool) -> String {
+    if enabled {
+        format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, label)
+    } else if label == url {
+        label.to_string()
+    } else {
+        format!("{} ({})", label, url)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_write() {
+        let mut config = Config::custom(
+            Config::default().colors,
+            HowToBugReport::new(
+                "Oh no! Humantalk testing has crashed (don't worry it was manually induced)"
+                    .to_string(),
+                "https://github.com/werdl/humantalk".to_string(),
+            ),
         );
+        let capture = testing::CaptureSink::new();
+        capture.install(&mut config);
 
-        let llvm_version_string = match rustc_info.llvm_version {
-            Some(version) => {
-                format!("{}.{}", version.major, version.minor)
-            }
-            None => "unknown".to_string(),
-        };
+        config.write(Severity::Info, "hello information world!");
+        capture.assert_logged(Severity::Info, "[info] hello information world!");
+    }
 
-        format!(
-            "{family}-{os}-{arch} - Rust version {}, running on LLVM {}. information stuff generated by humantalk {}",
-            rustc_info.short_version_string,
-            llvm_version_string,
-            VERSION
-        )
+    #[test]
+    fn test_default_trait() {
+        #[derive(Default)]
+        struct App {
+            config: Config,
+        }
+
+        fn make<T: Default>() -> T {
+            T::default()
+        }
+
+        assert_eq!(App::default().config.colors, make::<Config>().colors);
+        assert_eq!(
+            <Config as Default>::default().prefix_template,
+            DEFAULT_PREFIX_TEMPLATE
+        );
+    }
+
+    #[test]
+    fn test_machine_info() {
+        let info = Config::default().machine_info();
+        assert!(info.starts_with(std::env::consts::FAMILY));
+        assert!(info.ends_with(&version_info()));
+    }
+
+    #[cfg(not(feature = "max_level_error"))]
+    #[test]
+    fn test_quiet_and_silent() {
+        let mut config = Config::default();
+        assert!(config.should_write(&Severity::Warning));
+
+        config.set_quiet(true);
+        assert!(config.should_write(&Severity::Error));
+        assert!(!config.should_write(&Severity::Warning));
+        assert!(!config.should_write(&Severity::Info));
+
+        config.set_silent(true);
+        assert!(!config.should_write(&Severity::Error));
+
+        config.set_quiet(false);
+        assert!(!config.should_write(&Severity::Error));
     }
 
-    /// error fatally, crashing the program. then exits with error code `3`, indincating that erroring out has succeeded
-    pub fn fatal_error(&self, message: &str) {
-        let bug_report = match self.bug_report.clone() {
-            Some(x) => x,
-            None => HowToBugReport {
-                message: "Oh no! The program has crashed".to_string(),
-                url: "the appropriate place".to_string(),
-            },
+    #[test]
+    fn test_dedup() {
+        let mut dedup = Dedup::default();
+        let window = Duration::from_secs(5);
+        let start = Instant::now();
+        let key = |severity: Severity, message: &str| DedupKey {
+            severity,
+            message: message.to_string(),
+            scope: None,
+            fields: vec![],
+        };
+        let mut observe = |severity: Severity, message: &str, now: Instant| {
+            let (repeats, write) =
+                dedup.observe(key(severity, message), window, now, Config::default);
+            (repeats.map(|repeats| repeats.summary), write)
         };
-        let styled = style(format!(
-            "[FATAL] {}
-{}. Please submit a report to {}, along with a copy of this error message, which can also be found in crash_report.log as plaintext.
 
-",
-            message, bug_report.message, bug_report.url
-        ))
-        .red();
+        assert_eq!(observe(Severity::Warning, "disk full", start), (None, true));
+        for _ in 0..42 {
+            assert_eq!(
+                observe(Severity::Warning, "disk full", start),
+                (None, false)
+            );
+        }
 
-        println!("{}", styled);
+        // a different message flushes the repeats first
+        assert_eq!(
+            observe(Severity::Info, "retrying", start),
+            (Some("disk full (repeated 42x)".to_string()), true)
+        );
 
-        println!(
-            "{}",
-            style(format!("[PLATFORM INFO]\n{}", self.machine_info())).cyan()
+        // the same message after the window has passed is printed again
+        assert_eq!(observe(Severity::Info, "retrying", start), (None, false));
+        assert_eq!(
+            observe(Severity::Info, "retrying", start + window),
+            (Some("retrying (repeated 1x)".to_string()), true)
         );
 
-        let mut debug_file = std::fs::File::create("crash_report.log").unwrap_or_else(|_| {
-            println!("Failed to create debug file - just copy the information displayed above.");
-
-            std::process::exit(-1);
-        });
-
-        let _ = debug_file
-            .write(format!(
-                "[FATAL] {}
-{}. Please submit a report to {}, along with a copy of this error message, which can also be found in crash_report.log as plaintext.",
-                message, bug_report.message, bug_report.url
-            ).as_bytes())
-            .unwrap_or_else(|_| {
-                println!(
-                    "Failed to write to debug file - just copy the information displayed above."
-                );
+        // the same text from another scope is a different message
+        let mut scoped = key(Severity::Info, "retrying");
+        scoped.scope = Some("db".to_string());
+        assert!(
+            dedup
+                .observe(scoped, window, start + window, Config::default)
+                .1
+        );
+    }
 
-                std::process::exit(-1);
-            });
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_dedup_scoped() {
+        let (mut config, buffer) = buffered_config();
+        config.set_dedup_window(Duration::from_secs(60));
 
-        let _ = debug_file
-            .write(format!("\n[PLATFORM INFO]\n{}", self.machine_info()).as_bytes())
-            .unwrap_or_else(|_| {
-                println!(
-                    "Failed to write to debug file - just copy the information displayed above."
-                );
+        let network = config.scoped("network");
+        let db = config.scoped("db");
+        network.warning("connection refused");
+        network.warning("connection refused");
+        db.warning("connection refused");
+        db.with_field("retry", 2).warning("connection refused");
+        db.with_field("retry", 2).warning("connection refused");
+        drop((network, db));
+        drop(config);
+
+        assert_eq!(
+            buffer.contents(),
+            "[warning] network: connection refused\n\
+             [warning] network: connection refused (repeated 1x)\n\
+             [warning] db: connection refused\n\
+             [warning] db: connection refused retry=2\n\
+             [warning] db: connection refused (repeated 1x) retry=2\n"
+        );
+    }
 
-                std::process::exit(-1);
-            });
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_dedup_flush() {
+        let (mut config, buffer) = buffered_config();
+        config.set_dedup(Duration::from_secs(60));
 
-        std::process::exit(3)
+        for _ in 0..1474 {
+            config.warning("connection refused");
+        }
+        config.flush();
+        assert!(buffer
+            .contents()
+            .ends_with("[warning] connection refused (repeated 1473x)\n"));
+
+        let clone = config.clone();
+        for _ in 0..4 {
+            clone.info("retrying");
+        }
+        drop(clone);
+        assert!(!buffer.contents().contains("retrying (repeated"));
+        drop(config);
+
+        let contents = buffer.contents();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[3].ends_with("[info] retrying (repeated 3x)"));
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    #[test]
+    fn test_hyperlink() {
+        assert_eq!(
+            render_hyperlink("https://example.com", "report", true),
+            "\x1b]8;;https://example.com\x1b\\report\x1b]8;;\x1b\\"
+        );
+        assert_eq!(
+            render_hyperlink("https://example.com", "report", false),
+            "report (https://example.com)"
+        );
+        assert_eq!(
+            render_hyperlink("https://example.com", "https://example.com", false),
+            "https://example.com"
+        );
+        assert_eq!(
+            hyperlink("the appropriate place", "the appropriate place"),
+            "the appropriate place"
+        );
+
+        let mut config = Config::default();
+        config.set_hyperlinks(false);
+        assert_eq!(
+            config.link("https://example.com", "report"),
+            "report (https://example.com)"
+        );
+    }
 
     #[test]
-    fn test_write() {
-        let config = Config::custom(
+    fn test_severity_order() {
+        let mut severities = vec![
+            Severity::Info,
+            Severity::Error,
+            Severity::Debug,
+            Severity::Warning,
+        ];
+        severities.sort();
+        assert_eq!(
+            severities,
+            vec![
+                Severity::Debug,
+                Severity::Info,
+                Severity::Warning,
+                Severity::Error
+            ]
+        );
+
+        assert!(Severity::Error > Severity::Warning);
+        assert!(Severity::Error.at_least(&Severity::Debug));
+        assert!(!Severity::Info.at_least(&Severity::Warning));
+    }
+
+    #[cfg(not(feature = "max_level_error"))]
+    #[test]
+    fn test_min_severity() {
+        let mut config = Config::default();
+        assert_eq!(
+            config.should_write(&Severity::Debug),
+            cfg!(debug_assertions) && !cfg!(feature = "max_level_info")
+        );
+
+        config.set_min_severity(Severity::Warning);
+        assert!(config.should_write(&Severity::Error));
+        assert!(config.should_write(&Severity::Warning));
+        assert!(!config.should_write(&Severity::Info));
+        assert!(!config.should_write(&Severity::Debug));
+
+        config.set_min_severity(Severity::Error);
+        config.set_quiet(true);
+        assert!(config.should_write(&Severity::Error));
+    }
+
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_macros() {
+        let mut config = Config::default();
+        config.set_recent_log_capacity(10);
+
+        let count = 3;
+        crate::info!(config, "{} files", count);
+        crate::hterror!(config, "plain");
+        crate::htwrite!(config, Severity::Warning, "{:>3}", count);
+
+        config.set_min_severity(Severity::Warning);
+        crate::htdebug!(config, "{}", count);
+        crate::debug!(config, "{}", count);
+
+        let recent = config.recent.lock().unwrap();
+        let messages: Vec<&str> = recent
+            .entries()
+            .map(|entry| entry.message.as_str())
+            .collect();
+        assert_eq!(messages, vec!["3 files", "plain", "  3"]);
+    }
+
+    /// a writer that can be handed to a config while the test keeps hold of what was written
+    #[derive(Clone, Default)]
+    pub(crate) struct SharedBuffer(pub(crate) Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        pub(crate) fn contents(&self) -> String {
+            console::strip_ansi_codes(&String::from_utf8_lossy(&self.0.lock().unwrap())).to_string()
+        }
+    }
+
+    /// a default config writing only to the returned buffer, without colors
+    pub(crate) fn buffered_config() -> (Config, SharedBuffer) {
+        let buffer = SharedBuffer::default();
+        let mut config = Config::default();
+        config.output = Arc::new(Mutex::new(Output::new(Sink::new(buffer.clone(), false))));
+        (config, buffer)
+    }
+
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_buffered() {
+        let (mut config, buffer) = buffered_config();
+
+        config.info("unbuffered");
+        assert!(buffer.contents().contains("unbuffered"));
+
+        config.set_buffered(true);
+        config.info("buffered");
+        assert!(!buffer.contents().contains("[info] buffered"));
+
+        config.flush();
+        assert!(buffer.contents().contains("[info] buffered"));
+
+        config.info("dropped");
+        drop(config);
+        assert!(buffer.contents().contains("[info] dropped"));
+    }
+
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_shared_state() {
+        let (config, buffer) = buffered_config();
+
+        let threads: Vec<_> = (0..2)
+            .map(|i| {
+                let config = config.clone();
+                std::thread::spawn(move || {
+                    for j in 0..50 {
+                        config.warning(&format!("thread {} message {}", i, j));
+                        config.write_once("started", Severity::Info, "started");
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(config.count(&Severity::Warning), 100);
+        assert_eq!(config.count(&Severity::Info), 1);
+        assert_eq!(buffer.contents().lines().count(), 101);
+    }
+
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_flush_severity() {
+        let (mut config, buffer) = buffered_config();
+        config.set_buffered(true);
+
+        config.info("batched");
+        config.info("also batched");
+        assert_eq!(buffer.contents(), "");
+
+        config.warning("flushed");
+        let lines: Vec<String> = buffer.contents().lines().map(str::to_string).collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with("batched") && lines[2].ends_with("[warning] flushed"));
+
+        config.set_flush_severity(None);
+        config.error("held");
+        assert!(!buffer.contents().contains("held"));
+
+        config.set_flush_severity(Some(Severity::Info));
+        config.write_all(Severity::Info, &["row"]);
+        assert!(buffer.contents().ends_with("[info] row\n"));
+    }
+
+    #[test]
+    fn test_static_min_severity() {
+        assert_eq!(
+            STATIC_MIN_SEVERITY == Severity::Trace,
+            !cfg!(feature = "max_level_debug")
+        );
+        assert_eq!(
+            STATIC_MIN_SEVERITY <= Severity::Debug,
+            !cfg!(feature = "max_level_info")
+        );
+
+        let mut config = Config::default();
+        let capture = testing::CaptureSink::new();
+        capture.install(&mut config);
+
+        config.error("kept");
+        config.info("maybe stripped");
+        crate::htinfo!(config, "{}", "maybe stripped");
+        assert_eq!(capture.messages_of(Severity::Error).len(), 1);
+        assert_eq!(
+            capture.messages_of(Severity::Info).len(),
+            if cfg!(feature = "max_level_warn") {
+                0
+            } else {
+                2
+            }
+        );
+    }
+
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_write_batch() {
+        let mut config = Config::default();
+        config.set_min_severity(Severity::Info);
+        let capture = testing::CaptureSink::new();
+        capture.install(&mut config);
+
+        let threads: Vec<_> = (0..4)
+            .map(|i| {
+                let config = config.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        let header = format!("report {}", i);
+                        config.write_batch(&[
+                            (Severity::Warning, &header),
+                            (Severity::Debug, "hidden"),
+                            (Severity::Info, "first"),
+                            (Severity::Info, "second"),
+                        ]);
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let entries = capture.entries();
+        assert_eq!(entries.len(), 1200);
+        for block in entries.chunks(3) {
+            assert!(block[0].1.contains("[warning] report "));
+            assert!(block[1].1.ends_with("first") && block[2].1.ends_with("second"));
+        }
+        assert_eq!(config.count(&Severity::Debug), 400);
+    }
+
+    #[cfg(not(feature = "max_level_error"))]
+    #[test]
+    fn test_write_all() {
+        let (mut config, buffer) = buffered_config();
+
+        config.write_all(Severity::Warning, &["one", "two", "three"]);
+        let contents = buffer.contents();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with("[warning] one") && lines[2].ends_with("[warning] three"));
+
+        config.set_quiet(true);
+        config.write_all(Severity::Info, &["hidden", "also hidden"]);
+        assert_eq!(buffer.contents().lines().count(), 3);
+        assert_eq!(config.count(&Severity::Info), 2);
+    }
+
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_write_colored() {
+        let buffer = SharedBuffer::default();
+        let mut config = Config::default();
+        config.output = Arc::new(Mutex::new(Output::new(Sink::new(buffer.clone(), true))));
+
+        config.write_colored(Severity::Info, "highlighted", Color::Magenta);
+        config.info("normal");
+
+        let raw = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = raw.lines().collect();
+        assert!(lines[0].contains("\u{1b}[38;5;5m") && lines[0].contains("highlighted"));
+        assert!(lines[1].contains("\u{1b}[38;5;2m") && !lines[1].contains("\u{1b}[38;5;5m"));
+        assert_eq!(config.get_color(&Severity::Info), Color::Green);
+    }
+
+    #[test]
+    fn test_multiline() {
+        let (mut config, buffer) = buffered_config();
+        config.set_prefix_template("[{severity}] ");
+
+        config.error("{\n  \"code\": 7\n}");
+        assert_eq!(
+            buffer.contents(),
+            "[error] {\n          \"code\": 7\n        }\n"
+        );
+    }
+
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_tee_file() {
+        let buffer = SharedBuffer::default();
+        let mut config = Config::default();
+        config.output = Arc::new(Mutex::new(Output::new(Sink::new(buffer.clone(), true))));
+
+        let path = scratch_dir("tee").join("app.log");
+        std::fs::write(&path, "earlier\n").unwrap();
+        config.set_tee_file(path.clone());
+
+        config.warning("mirrored");
+        config.section("Done");
+        config.tee_file = None;
+        config.info("terminal only");
+
+        let file = std::fs::read_to_string(&path).unwrap();
+        assert!(!file.contains('\u{1b}'));
+        assert!(file.starts_with("earlier\n"));
+        assert!(file.contains("[warning] mirrored\n── Done ─"));
+        assert!(!file.contains("terminal only"));
+        assert!(buffer.contents().contains("[warning] mirrored"));
+    }
+
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_relative_time() {
+        let (mut config, buffer) = buffered_config();
+        config.set_relative_time(true);
+
+        let clone = config.clone();
+        std::thread::sleep(Duration::from_millis(20));
+        clone.info("later");
+
+        let line = buffer.contents();
+        let seconds: f64 = line["[+".len()..line.find("s]").unwrap()].parse().unwrap();
+        assert!(seconds >= 0.02, "{}", line);
+        assert!(line.ends_with("s] [info] later\n"));
+    }
+
+    pub(crate) fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("humantalk-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let mut config = Config::custom(
             Config::default().colors,
-            HowToBugReport::new(
-                "Oh no! Humantalk testing has crashed (don't worry it was manually induced)".to_string(),
-                "https://github.com/werdl/humantalk".to_string()
-            )
+            HowToBugReport::new("crashed".to_string(), "https://example.com".to_string()),
         );
-        config.write(Severity::Debug, "hello world!");
-        config.write(Severity::Info, "hello information world!")
+        config.set_color(Severity::Debug, Color::Color256(208));
+        config.set_crash_report_path(PathBuf::from("/tmp/crash.log"));
+        config.set_crash_report_format(CrashReportFormat::Json);
+        config.set_min_severity(Severity::Info);
+        config.set_dedup_window(Duration::from_millis(1500));
+        config.set_recent_log_capacity(20);
+
+        let json = serde_json::to_string(&config).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["colors"]["error"], "red");
+        assert_eq!(value["colors"]["debug"], 208);
+        assert_eq!(value["min_severity"], "info");
+        assert_eq!(value["recent_log_capacity"], 20);
+
+        let from_json: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&from_json).unwrap(), json);
+        assert_eq!(from_json.get_color(&Severity::Debug), Color::Color256(208));
+
+        let toml = toml::to_string(&config).unwrap();
+        let from_toml: Config = toml::from_str(&toml).unwrap();
+        assert_eq!(toml::to_string(&from_toml).unwrap(), toml);
+        assert_eq!(serde_json::to_string(&from_toml).unwrap(), json);
+
+        assert!(serde_json::from_str::<Config>(&json.replace("\"red\"", "\"mauve\"")).is_err());
     }
 }
@@ -0,0 +1,43 @@
+//! messages tagged with the source location they were written from
+
+use crate::{Config, Severity};
+
+impl Config {
+    /// write a message tagged with a source location such as `src/main.rs:42`, shown dimmed before the message like
+    /// `[error] [src/main.rs:42] boom`. usually called through `error_at!`, `info_at!` etc., which fill in the location.
+    /// the location is left out if `show_locations` is off. located messages are never collapsed by `dedup_window`
+    pub fn write_located(&self, severity: Severity, message: &str, location: &str) {
+        self.counts.record(&severity);
+
+        if !self.should_write(&severity) {
+            return;
+        }
+
+        self.print(&severity, message, Some(location), &[]);
+    }
+
+    /// set whether the locations given to `write_located` are shown
+    pub fn set_show_locations(&mut self, show_locations: bool) {
+        self.show_locations = show_locations;
+    }
+}
+
+#[cfg(all(test, not(feature = "max_level_error")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_write_located() {
+        let (mut config, buffer) = crate::test::buffered_config();
+
+        crate::error_at!(config, "boom {}", 1);
+        let line = line!() - 1;
+        assert!(buffer
+            .contents()
+            .ends_with(&format!("[error] [src/location.rs:{}] boom 1\n", line)));
+
+        config.set_show_locations(false);
+        config.write_located(Severity::Warning, "hidden", "src/main.rs:1");
+        assert!(buffer.contents().ends_with("[warning] hidden\n"));
+    }
+}
@@ -0,0 +1,60 @@
+//! conversions between humantalk's severities and the `log` crate's levels, behind the `log` feature
+
+use crate::Severity;
+
+/// `log`'s levels as severities of the same name, with `Warn` as `Warning`
+impl From<log::Level> for Severity {
+    fn from(level: log::Level) -> Severity {
+        match level {
+            log::Level::Error => Severity::Error,
+            log::Level::Warn => Severity::Warning,
+            log::Level::Info => Severity::Info,
+            log::Level::Debug => Severity::Debug,
+            log::Level::Trace => Severity::Trace,
+        }
+    }
+}
+
+/// severities as `log`'s levels of the same name, with `Warning` as `Warn`
+impl From<Severity> for log::Level {
+    fn from(severity: Severity) -> log::Level {
+        match severity {
+            Severity::Error => log::Level::Error,
+            Severity::Warning => log::Level::Warn,
+            Severity::Info => log::Level::Info,
+            Severity::Debug => log::Level::Debug,
+            Severity::Trace => log::Level::Trace,
+        }
+    }
+}
+
+/// a severity as the `log` filter that lets it and everything more severe through, like `min_severity`
+impl From<Severity> for log::LevelFilter {
+    fn from(severity: Severity) -> log::LevelFilter {
+        log::Level::from(severity).to_level_filter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_log_levels() {
+        let levels = [
+            (log::Level::Error, Severity::Error),
+            (log::Level::Warn, Severity::Warning),
+            (log::Level::Info, Severity::Info),
+            (log::Level::Debug, Severity::Debug),
+            (log::Level::Trace, Severity::Trace),
+        ];
+
+        for (level, severity) in levels {
+            assert_eq!(Severity::from(level), severity);
+            assert_eq!(log::Level::from(severity.clone()), level);
+            assert_eq!(log::LevelFilter::from(severity), level.to_level_filter());
+        }
+
+        assert!(log::LevelFilter::from(Severity::Warning) < log::LevelFilter::Info);
+    }
+}
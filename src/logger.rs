@@ -0,0 +1,104 @@
+//! a cheap handle to a config, for sharing one between many threads
+
+use std::{ops::Deref, sync::Arc};
+
+use crate::Config;
+
+/// a config behind an `Arc`, so cloning it is cheap whatever the config holds. it derefs to the config, so every
+/// logging method is available on it. `Config` and `Logger` are both `Send` and `Sync`, and messages written from
+/// several threads at once are written whole, one line at a time
+///
+/// # Examples
+/// ```rust
+/// use humantalk::Config;
+/// let logger = Config::default().into_logger();
+///
+/// let workers: Vec<_> = (0..4)
+///     .map(|i| {
+///         let logger = logger.clone();
+///         std::thread::spawn(move || logger.info(&format!("worker {} done", i)))
+///     })
+///     .collect();
+///
+/// for worker in workers {
+///     worker.join().unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Logger(Arc<Config>);
+
+impl Logger {
+    /// a handle to `config`
+    pub fn new(config: Config) -> Logger {
+        Logger(Arc::new(config))
+    }
+}
+
+impl Deref for Logger {
+    type Target = Config;
+
+    fn deref(&self) -> &Config {
+        &self.0
+    }
+}
+
+impl From<Config> for Logger {
+    fn from(config: Config) -> Logger {
+        Logger::new(config)
+    }
+}
+
+impl Config {
+    /// a cheap handle to this config, see `Logger`
+    pub fn into_logger(self) -> Logger {
+        Logger::new(self)
+    }
+}
+
+/// fails to compile if either type stops being shareable between threads
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Config>();
+    assert_send_sync::<Logger>();
+};
+
+#[cfg(all(test, not(feature = "max_level_warn")))]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::Severity;
+
+    #[test]
+    fn test_logger_threads() {
+        const THREADS: usize = 8;
+        const MESSAGES: usize = 10_000;
+
+        let (config, buffer) = crate::test::buffered_config();
+        let logger = config.into_logger();
+
+        let threads: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let logger = logger.clone();
+                std::thread::spawn(move || {
+                    for i in 0..MESSAGES {
+                        logger.info(&format!("thread {} message {}", t, i));
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let contents = buffer.contents();
+        let mut seen = HashSet::new();
+        for line in contents.lines() {
+            let message = line.split_once("[info] ").expect("a whole line").1;
+            assert!(message.starts_with("thread ") && message.matches("message").count() == 1);
+            assert!(seen.insert(message.to_string()));
+        }
+
+        assert_eq!(seen.len(), THREADS * MESSAGES);
+        assert_eq!(logger.count(&Severity::Info), THREADS * MESSAGES);
+    }
+}
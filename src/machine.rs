@@ -0,0 +1,387 @@
+//! best-effort detection of the environment and hardware the program is running on
+
+use std::sync::OnceLock;
+
+use crate::{
+    json_string,
+    version::{describe, enabled_features},
+    VERSION,
+};
+
+/// information about the machine and toolchain, included in crash reports. displays as the string returned by
+/// `Config::machine_info`
+///
+/// # Examples
+/// ```rust
+/// use humantalk::Config;
+/// let info = Config::default().machine_info_struct();
+///
+/// println!("running on {} {}", info.os, info.arch);
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MachineInfo {
+    /// operating system family, such as `unix` or `windows`
+    pub family: String,
+
+    /// operating system, such as `linux` or `macos`
+    pub os: String,
+
+    /// CPU architecture, such as `x86_64` or `aarch64`
+    pub arch: String,
+
+    /// container or virtualization layer, such as `docker` or `wsl`, or `native`
+    pub environment: String,
+
+    /// number of logical CPUs, if it could be determined
+    pub cpu_count: Option<usize>,
+
+    /// total physical memory in bytes, if it could be read
+    pub memory_bytes: Option<u64>,
+
+    /// version of the rust toolchain, or `unknown`
+    pub rustc_version: String,
+
+    /// version of LLVM used by the toolchain, if known
+    pub llvm_version: Option<String>,
+
+    /// version of humantalk
+    pub humantalk_version: String,
+
+    /// optional features humantalk was built with, such as `serde`
+    pub humantalk_features: Vec<String>,
+
+    /// name of the machine, or `unknown`. `None` if left out with `Config::set_include_identity`
+    pub hostname: Option<String>,
+
+    /// name of the user running the program, or `unknown`. `None` if left out with `Config::set_include_identity`
+    pub username: Option<String>,
+}
+
+/// machine info gathered on first use, since none of it changes while the program runs
+static MACHINE_INFO: OnceLock<MachineInfo> = OnceLock::new();
+
+impl MachineInfo {
+    /// the machine info, gathered the first time it is asked for and reused after that
+    pub(crate) fn get() -> &'static MachineInfo {
+        MACHINE_INFO.get_or_init(MachineInfo::collect)
+    }
+
+    fn collect() -> MachineInfo {
+        let (rustc_version, llvm_version) = toolchain(
+            option_env!("HUMANTALK_RUSTC_VERSION"),
+            option_env!("HUMANTALK_LLVM_VERSION"),
+        );
+
+        MachineInfo {
+            family: family(std::env::consts::FAMILY, std::env::consts::ARCH),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            environment: environment(),
+            cpu_count: cpu_count(),
+            memory_bytes: total_memory(),
+            rustc_version,
+            llvm_version,
+            humantalk_version: VERSION.to_string(),
+            humantalk_features: enabled_features(),
+            hostname: Some(hostname().unwrap_or_else(|| "unknown".to_string())),
+            username: Some(username().unwrap_or_else(|| "unknown".to_string())),
+        }
+    }
+
+    /// the same info without the hostname and username
+    pub(crate) fn without_identity(mut self) -> MachineInfo {
+        self.hostname = None;
+        self.username = None;
+        self
+    }
+
+    /// the info as a JSON object, for JSON crash reports
+    pub(crate) fn json(&self) -> String {
+        format!(
+            "{{\"family\":{},\"os\":{},\"arch\":{},\"environment\":{},\"cpu_count\":{},\"memory_bytes\":{},\
+\"rustc_version\":{},\"llvm_version\":{},\"humantalk_version\":{},\"humantalk_features\":[{}],\"hostname\":{},\"username\":{}}}",
+            json_string(&self.family),
+            json_string(&self.os),
+            json_string(&self.arch),
+            json_string(&self.environment),
+            self.cpu_count.map_or("null".to_string(), |count| count.to_string()),
+            self.memory_bytes.map_or("null".to_string(), |bytes| bytes.to_string()),
+            json_string(&self.rustc_version),
+            self.llvm_version.as_deref().map_or("null".to_string(), json_string),
+            json_string(&self.humantalk_version),
+            self.humantalk_features.iter().map(|feature| json_string(feature)).collect::<Vec<_>>().join(","),
+            self.hostname.as_deref().map_or("null".to_string(), json_string),
+            self.username.as_deref().map_or("null".to_string(), json_string)
+        )
+    }
+}
+
+impl std::fmt::Display for MachineInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{}-{} ({}, {} CPUs, {} memory{}) - Rust version {}, running on LLVM {}. information stuff generated by {}",
+            self.family,
+            self.os,
+            self.arch,
+            self.environment,
+            self.cpu_count.map_or("unknown".to_string(), |count| count.to_string()),
+            format_memory(self.memory_bytes),
+            match (&self.hostname, &self.username) {
+                (Some(hostname), Some(username)) => format!(", {}@{}", username, hostname),
+                _ => String::new(),
+            },
+            self.rustc_version,
+            self.llvm_version.as_deref().unwrap_or("unknown"),
+            describe(&self.humantalk_version, &self.humantalk_features)
+        )
+    }
+}
+
+/// rustc and LLVM version strings of the toolchain humantalk was compiled with, as recorded by the build script.
+/// rustc is never run at runtime, so this works on machines without a toolchain. `rustc` is `unknown` if the build
+/// script could not read the version
+fn toolchain(rustc: Option<&str>, llvm: Option<&str>) -> (String, Option<String>) {
+    (
+        rustc.unwrap_or("unknown").to_string(),
+        llvm.map(str::to_string),
+    )
+}
+
+/// the os family, or `wasm` on wasm targets, which have no family and no os to speak of
+fn family(family: &str, arch: &str) -> String {
+    match family {
+        "" if arch.starts_with("wasm") => "wasm".to_string(),
+        "" => "unknown".to_string(),
+        family => family.to_string(),
+    }
+}
+
+/// the machine's hostname, from the environment or, on linux, the kernel
+fn hostname() -> Option<String> {
+    ["HOSTNAME", "COMPUTERNAME"]
+        .into_iter()
+        .find_map(|name| std::env::var(name).ok())
+        .or_else(|| std::fs::read_to_string("/proc/sys/kernel/hostname").ok())
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|hostname| hostname.trim().to_string())
+        .filter(|hostname| !hostname.is_empty())
+}
+
+/// the name of the current user, from `$USER` or `%USERNAME%`
+fn username() -> Option<String> {
+    ["USER", "USERNAME", "LOGNAME"]
+        .into_iter()
+        .find_map(|name| std::env::var(name).ok())
+        .filter(|username| !username.is_empty())
+}
+
+/// environment reported when no container or virtualization is detected
+const NATIVE: &str = "native";
+
+/// a short name for the container or virtualization layer the program is running in, such as `docker` or `wsl`, or
+/// `native` if none is detected. detection only looks at well known files and environment variables, and never fails
+fn environment() -> String {
+    detect_environment(
+        |path| std::path::Path::new(path).exists(),
+        |name| std::env::var(name).ok().filter(|value| !value.is_empty()),
+        |path| std::fs::read_to_string(path).ok(),
+    )
+}
+
+fn detect_environment(
+    exists: impl Fn(&str) -> bool,
+    var: impl Fn(&str) -> Option<String>,
+    read: impl Fn(&str) -> Option<String>,
+) -> String {
+    if var("KUBERNETES_SERVICE_HOST").is_some() {
+        return "kubernetes".to_string();
+    }
+
+    if exists("/.dockerenv") {
+        return "docker".to_string();
+    }
+
+    if exists("/run/.containerenv") {
+        return "podman".to_string();
+    }
+
+    // set by systemd-nspawn, podman, lxc and others to the name of the container manager
+    if let Some(container) = var("container") {
+        return container;
+    }
+
+    let wsl_kernel = read("/proc/sys/kernel/osrelease")
+        .is_some_and(|release| release.to_lowercase().contains("microsoft"));
+    if var("WSL_DISTRO_NAME").is_some() || wsl_kernel {
+        return "wsl".to_string();
+    }
+
+    NATIVE.to_string()
+}
+
+/// the number of logical CPUs available to the program, if it can be determined
+fn cpu_count() -> Option<usize> {
+    std::thread::available_parallelism().ok().map(usize::from)
+}
+
+/// total physical memory in bytes. only read on linux, from `/proc/meminfo`
+fn total_memory() -> Option<u64> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+
+    parse_meminfo(&std::fs::read_to_string("/proc/meminfo").ok()?)
+}
+
+/// the `MemTotal` line of `/proc/meminfo`, which is given in KiB
+fn parse_meminfo(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|line| line.starts_with("MemTotal:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+
+    Some(kib * 1024)
+}
+
+/// a number of bytes in GiB to one decimal place, like `15.5 GiB`, or `unknown`
+fn format_memory(bytes: Option<u64>) -> String {
+    match bytes {
+        Some(bytes) => format!("{:.1} GiB", bytes as f64 / (1024 * 1024 * 1024) as f64),
+        None => "unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_detect_environment() {
+        let none = |_: &str| None;
+
+        assert_eq!(detect_environment(|_| false, none, none), NATIVE);
+        assert_eq!(
+            detect_environment(|path| path == "/.dockerenv", none, none),
+            "docker"
+        );
+        assert_eq!(
+            detect_environment(
+                |_| false,
+                |name| (name == "container").then(|| "lxc".to_string()),
+                none
+            ),
+            "lxc"
+        );
+        assert_eq!(
+            detect_environment(
+                |_| false,
+                none,
+                |_| Some("5.15.90.1-microsoft-standard-WSL2".to_string())
+            ),
+            "wsl"
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let info = MachineInfo {
+            family: "unix".to_string(),
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            environment: "docker".to_string(),
+            cpu_count: Some(8),
+            memory_bytes: None,
+            rustc_version: "rustc 1.78.0 (9b00956e5 2024-04-29)".to_string(),
+            llvm_version: Some("18.1".to_string()),
+            humantalk_version: "0.1.1".to_string(),
+            humantalk_features: vec![],
+            hostname: None,
+            username: None,
+        };
+
+        assert_eq!(
+            info.to_string(),
+            "unix-linux-x86_64 (docker, 8 CPUs, unknown memory) - Rust version rustc 1.78.0 (9b00956e5 2024-04-29), \
+running on LLVM 18.1. information stuff generated by humantalk 0.1.1"
+        );
+        assert_eq!(
+            Config::default().machine_info(),
+            Config::default().machine_info_struct().to_string()
+        );
+
+        let info = MachineInfo {
+            hostname: Some("build-01".to_string()),
+            username: Some("ci".to_string()),
+            ..info
+        };
+        assert!(info
+            .to_string()
+            .starts_with("unix-linux-x86_64 (docker, 8 CPUs, unknown memory, ci@build-01) - "));
+
+        let info = MachineInfo {
+            humantalk_features: vec!["serde".to_string()],
+            ..info
+        };
+        assert!(info
+            .to_string()
+            .ends_with("generated by humantalk 0.1.1 (features: serde)"));
+    }
+
+    #[test]
+    fn test_identity() {
+        let info = Config::default().machine_info_struct();
+        assert!(info.hostname.is_some_and(|hostname| !hostname.is_empty()));
+        assert!(info.username.is_some_and(|username| !username.is_empty()));
+
+        let mut config = Config::default();
+        config.set_include_identity(false);
+        let info = config.machine_info_struct();
+        assert_eq!((info.hostname, info.username), (None, None));
+    }
+
+    #[test]
+    fn test_unknown_toolchain() {
+        assert_eq!(toolchain(None, None), ("unknown".to_string(), None));
+
+        let mut info = MachineInfo::collect();
+        (info.rustc_version, info.llvm_version) = toolchain(None, None);
+        assert!(info
+            .to_string()
+            .contains("Rust version unknown, running on LLVM unknown."));
+        assert!(info
+            .json()
+            .contains("\"rustc_version\":\"unknown\",\"llvm_version\":null"));
+
+        let (rustc, _) = toolchain(option_env!("HUMANTALK_RUSTC_VERSION"), None);
+        assert!(rustc.starts_with("rustc "));
+    }
+
+    #[test]
+    fn test_cached() {
+        let first: *const MachineInfo = MachineInfo::get();
+        let second: *const MachineInfo = MachineInfo::get();
+        assert_eq!(first, second);
+
+        let config = Config::default();
+        assert_eq!(config.machine_info(), config.machine_info());
+    }
+
+    #[test]
+    fn test_memory() {
+        let meminfo = "MemTotal:       16252928 kB\nMemFree:         1048576 kB\n";
+        assert_eq!(parse_meminfo(meminfo), Some(16252928 * 1024));
+        assert_eq!(parse_meminfo("MemFree: 1 kB"), None);
+
+        assert_eq!(format_memory(parse_meminfo(meminfo)), "15.5 GiB");
+        assert_eq!(format_memory(None), "unknown");
+        assert!(cpu_count().is_some_and(|count| count > 0));
+    }
+
+    #[test]
+    fn test_family() {
+        assert_eq!(family("unix", "x86_64"), "unix");
+        assert_eq!(family("", "wasm32"), "wasm");
+        assert_eq!(family("", "riscv32"), "unknown");
+    }
+}
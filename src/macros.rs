@@ -0,0 +1,287 @@
+//! `println!`-style macros for writing formatted messages
+
+/// write a formatted message at the given severity, like `config.write` with `format!` arguments.
+/// nothing is formatted if the message would be filtered out. without a config, the global config is used (see
+/// `set_global`), and the same goes for the other formatting macros
+///
+/// # Examples
+/// ```rust
+/// use humantalk::{htwrite, Config, Severity};
+/// let config = Config::default();
+///
+/// let retries = 3;
+/// htwrite!(config, Severity::Warning, "retrying ({} attempts left)", retries);
+/// htwrite!(Severity::Info, "written with the global config");
+/// ```
+#[macro_export]
+macro_rules! htwrite {
+    ($severity:expr, $fmt:literal $($arg:tt)*) => {
+        $crate::htwrite!($crate::global(), $severity, $fmt $($arg)*)
+    };
+    ($config:expr, $severity:expr, $($arg:tt)+) => {
+        $config.write_fmt($severity, ::std::format_args!($($arg)+))
+    };
+}
+
+/// shorthand for `htwrite!(config, Severity::Error, ...)`
+#[macro_export]
+macro_rules! hterror {
+    ($fmt:literal $($arg:tt)*) => {
+        $crate::hterror!($crate::global(), $fmt $($arg)*)
+    };
+    ($config:expr, $($arg:tt)+) => {
+        $crate::htwrite!($config, $crate::Severity::Error, $($arg)+)
+    };
+}
+
+/// shorthand for `htwrite!(config, Severity::Warning, ...)`. with the `max_level_error` feature it expands to nothing,
+/// like `htdebug!`
+#[cfg(not(feature = "max_level_error"))]
+#[macro_export]
+macro_rules! htwarning {
+    ($fmt:literal $($arg:tt)*) => {
+        $crate::htwarning!($crate::global(), $fmt $($arg)*)
+    };
+    ($config:expr, $($arg:tt)+) => {
+        $crate::htwrite!($config, $crate::Severity::Warning, $($arg)+)
+    };
+}
+
+/// shorthand for `htwrite!(config, Severity::Warning, ...)`. with the `max_level_error` feature it expands to nothing,
+/// like `htdebug!`
+#[cfg(feature = "max_level_error")]
+#[macro_export]
+macro_rules! htwarning {
+    ($fmt:literal $($arg:tt)*) => {{}};
+    ($config:expr, $($arg:tt)+) => {{
+        let _ = &$config;
+    }};
+}
+
+/// shorthand for `htwrite!(config, Severity::Info, ...)`. with the `max_level_warn` feature it expands to nothing, like
+/// `htdebug!`
+///
+/// # Examples
+/// ```rust
+/// use humantalk::{htinfo, Config};
+/// let config = Config::default();
+///
+/// let x = 42;
+/// htinfo!(config, "x = {}", x);
+/// ```
+#[cfg(not(feature = "max_level_warn"))]
+#[macro_export]
+macro_rules! htinfo {
+    ($fmt:literal $($arg:tt)*) => {
+        $crate::htinfo!($crate::global(), $fmt $($arg)*)
+    };
+    ($config:expr, $($arg:tt)+) => {
+        $crate::htwrite!($config, $crate::Severity::Info, $($arg)+)
+    };
+}
+
+/// shorthand for `htwrite!(config, Severity::Info, ...)`. with the `max_level_warn` feature it expands to nothing, like
+/// `htdebug!`
+#[cfg(feature = "max_level_warn")]
+#[macro_export]
+macro_rules! htinfo {
+    ($fmt:literal $($arg:tt)*) => {{}};
+    ($config:expr, $($arg:tt)+) => {{
+        let _ = &$config;
+    }};
+}
+
+/// shorthand for `htwrite!(config, Severity::Debug, ...)`. with the `strip-debug` or `max_level_info` features it
+/// expands to nothing, so neither the message nor its arguments end up in the binary
+#[cfg(not(feature = "max_level_info"))]
+#[macro_export]
+macro_rules! htdebug {
+    ($fmt:literal $($arg:tt)*) => {
+        $crate::htdebug!($crate::global(), $fmt $($arg)*)
+    };
+    ($config:expr, $($arg:tt)+) => {
+        $crate::htwrite!($config, $crate::Severity::Debug, $($arg)+)
+    };
+}
+
+/// shorthand for `htwrite!(config, Severity::Debug, ...)`. with the `strip-debug` or `max_level_info` features it
+/// expands to nothing, so neither the message nor its arguments end up in the binary
+#[cfg(feature = "max_level_info")]
+#[macro_export]
+macro_rules! htdebug {
+    ($fmt:literal $($arg:tt)*) => {{}};
+    ($config:expr, $($arg:tt)+) => {{
+        let _ = &$config;
+    }};
+}
+
+/// shorthand for `htwrite!(config, Severity::Trace, ...)`. with the `strip-debug` or `max_level_debug` features it
+/// expands to nothing, like `htdebug!`
+#[cfg(not(feature = "max_level_debug"))]
+#[macro_export]
+macro_rules! httrace {
+    ($fmt:literal $($arg:tt)*) => {
+        $crate::httrace!($crate::global(), $fmt $($arg)*)
+    };
+    ($config:expr, $($arg:tt)+) => {
+        $crate::htwrite!($config, $crate::Severity::Trace, $($arg)+)
+    };
+}
+
+/// shorthand for `htwrite!(config, Severity::Trace, ...)`. with the `strip-debug` or `max_level_debug` features it
+/// expands to nothing, like `htdebug!`
+#[cfg(feature = "max_level_debug")]
+#[macro_export]
+macro_rules! httrace {
+    ($fmt:literal $($arg:tt)*) => {{}};
+    ($config:expr, $($arg:tt)+) => {{
+        let _ = &$config;
+    }};
+}
+
+/// same as `htinfo!`
+///
+/// # Examples
+/// ```rust
+/// use humantalk::Config;
+/// let config = Config::default();
+///
+/// humantalk::info!(config, "listening on port {}", 8080);
+/// humantalk::debug!(config, "config = {:?}", config.min_severity); // removed entirely with `strip-debug`
+/// ```
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => {
+        $crate::htinfo!($($arg)+)
+    };
+}
+
+/// same as `htdebug!`, expanding to nothing with the `strip-debug` feature
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        $crate::htdebug!($($arg)+)
+    };
+}
+
+/// same as `httrace!`, expanding to nothing with the `strip-debug` feature
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        $crate::httrace!($($arg)+)
+    };
+}
+
+/// write a formatted message at the given severity, tagged with the file and line it was written from. see
+/// `Config::write_located`
+///
+/// # Examples
+/// ```rust
+/// use humantalk::{htwrite_at, Config, Severity};
+/// let config = Config::default();
+///
+/// htwrite_at!(config, Severity::Warning, "{} retries left", 3); // [warning] [src/main.rs:5] 3 retries left
+/// ```
+#[macro_export]
+macro_rules! htwrite_at {
+    ($config:expr, $severity:expr, $($arg:tt)+) => {
+        $config.write_located(
+            $severity,
+            &::std::format!($($arg)+),
+            ::std::concat!(::std::file!(), ":", ::std::line!()),
+        )
+    };
+}
+
+/// shorthand for `htwrite_at!(config, Severity::Error, ...)`
+///
+/// # Examples
+/// ```rust
+/// use humantalk::{error_at, Config};
+/// let config = Config::default();
+///
+/// error_at!(config, "boom"); // [error] [src/main.rs:5] boom
+/// ```
+#[macro_export]
+macro_rules! error_at {
+    ($config:expr, $($arg:tt)+) => {
+        $crate::htwrite_at!($config, $crate::Severity::Error, $($arg)+)
+    };
+}
+
+/// shorthand for `htwrite_at!(config, Severity::Warning, ...)`, expanding to nothing with the `max_level_error` feature
+#[cfg(not(feature = "max_level_error"))]
+#[macro_export]
+macro_rules! warning_at {
+    ($config:expr, $($arg:tt)+) => {
+        $crate::htwrite_at!($config, $crate::Severity::Warning, $($arg)+)
+    };
+}
+
+/// shorthand for `htwrite_at!(config, Severity::Warning, ...)`, expanding to nothing with the `max_level_error` feature
+#[cfg(feature = "max_level_error")]
+#[macro_export]
+macro_rules! warning_at {
+    ($config:expr, $($arg:tt)+) => {{
+        let _ = &$config;
+    }};
+}
+
+/// shorthand for `htwrite_at!(config, Severity::Info, ...)`, expanding to nothing with the `max_level_warn` feature
+#[cfg(not(feature = "max_level_warn"))]
+#[macro_export]
+macro_rules! info_at {
+    ($config:expr, $($arg:tt)+) => {
+        $crate::htwrite_at!($config, $crate::Severity::Info, $($arg)+)
+    };
+}
+
+/// shorthand for `htwrite_at!(config, Severity::Info, ...)`, expanding to nothing with the `max_level_warn` feature
+#[cfg(feature = "max_level_warn")]
+#[macro_export]
+macro_rules! info_at {
+    ($config:expr, $($arg:tt)+) => {{
+        let _ = &$config;
+    }};
+}
+
+/// shorthand for `htwrite_at!(config, Severity::Debug, ...)`, expanding to nothing with the `strip-debug` or
+/// `max_level_info` features
+#[cfg(not(feature = "max_level_info"))]
+#[macro_export]
+macro_rules! debug_at {
+    ($config:expr, $($arg:tt)+) => {
+        $crate::htwrite_at!($config, $crate::Severity::Debug, $($arg)+)
+    };
+}
+
+/// shorthand for `htwrite_at!(config, Severity::Debug, ...)`, expanding to nothing with the `strip-debug` or
+/// `max_level_info` features
+#[cfg(feature = "max_level_info")]
+#[macro_export]
+macro_rules! debug_at {
+    ($config:expr, $($arg:tt)+) => {{
+        let _ = &$config;
+    }};
+}
+
+/// an `AppInfo` with the name and version of the crate it is used in, read from cargo at compile time
+///
+/// # Examples
+/// ```rust
+/// use humantalk::{app_info, Config};
+/// let mut config = Config::default();
+///
+/// config.set_app_info(app_info!());
+/// ```
+#[macro_export]
+macro_rules! app_info {
+    () => {
+        $crate::AppInfo {
+            name: ::std::env!("CARGO_PKG_NAME").to_string(),
+            version: ::std::env!("CARGO_PKG_VERSION").to_string(),
+            git_commit: ::std::option::Option::None,
+            build_date: ::std::option::Option::None,
+        }
+    };
+}
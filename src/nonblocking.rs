@@ -0,0 +1,331 @@
+//! writing to the sinks on a background thread, so logging never waits for IO
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+        Arc, Mutex, PoisonError,
+    },
+    thread::JoinHandle,
+};
+
+use crate::{output::Output, Config, Severity, Sink};
+
+/// how many lines can wait for the background thread by default
+pub const DEFAULT_NONBLOCKING_CAPACITY: usize = 1024;
+
+/// what a nonblocking config does with a message when the background thread has fallen behind and its queue is full
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum FullBehavior {
+    /// wait for room in the queue, so no message is lost
+    #[default]
+    Block,
+
+    /// drop the message and count it, see `WorkerGuard::dropped`
+    Drop,
+}
+
+/// options for `Config::into_nonblocking_with`
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct NonblockingOptions {
+    /// how many lines can wait for the background thread
+    pub capacity: usize,
+
+    /// what to do with a message when the queue is full
+    pub when_full: FullBehavior,
+}
+
+impl Default for NonblockingOptions {
+    fn default() -> Self {
+        NonblockingOptions {
+            capacity: DEFAULT_NONBLOCKING_CAPACITY,
+            when_full: FullBehavior::Block,
+        }
+    }
+}
+
+/// keeps the background thread of a nonblocking config running. dropping it writes out every queued message, flushes
+/// the sinks and joins the thread. anything written after that is written straight away, as if the config was never
+/// made nonblocking
+#[must_use = "dropping the guard stops the background thread straight away"]
+#[derive(Debug)]
+pub struct WorkerGuard {
+    sender: SyncSender<Job>,
+    thread: Option<JoinHandle<()>>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl WorkerGuard {
+    /// how many messages were dropped because the queue was full, with `FullBehavior::Drop`
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for WorkerGuard {
+    fn drop(&mut self) {
+        let _ = self.sender.send(Job::Stop);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Config {
+    /// write to the sinks on a background thread rather than in the calling thread, with `NonblockingOptions`'
+    /// defaults. lines are styled as usual and queued in order. `flush`, and so `fatal_error`, waits for everything
+    /// queued to be written. keep the returned guard alive for as long as the config is used, as dropping it stops
+    /// the thread. clones made before this call keep writing in their own thread
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::Config;
+    /// let (config, _guard) = Config::default().into_nonblocking();
+    ///
+    /// config.info("written by the background thread");
+    /// ```
+    pub fn into_nonblocking(self) -> (Config, WorkerGuard) {
+        self.into_nonblocking_with(NonblockingOptions::default())
+    }
+
+    /// write to the sinks on a background thread like `into_nonblocking`, with the queue set up as in `options`
+    pub fn into_nonblocking_with(mut self, options: NonblockingOptions) -> (Config, WorkerGuard) {
+        let (sender, receiver) = mpsc::sync_channel(options.capacity);
+        let dropped = Arc::new(AtomicU64::new(0));
+
+        let inner = self.output.clone();
+        let thread = {
+            let inner = inner.clone();
+            std::thread::Builder::new()
+                .name("humantalk".to_string())
+                .spawn(move || run(&inner, receiver))
+                .expect("failed to spawn the humantalk writer thread")
+        };
+
+        self.output = Arc::new(Mutex::new(Output::nonblocking(Worker {
+            sender: sender.clone(),
+            when_full: options.when_full,
+            dropped: dropped.clone(),
+            inner,
+        })));
+
+        let guard = WorkerGuard {
+            sender,
+            thread: Some(thread),
+            dropped,
+        };
+        (self, guard)
+    }
+}
+
+/// something for the background thread to do
+enum Job {
+    Line(Severity, String, bool),
+    Status(Option<String>),
+    Add(Sink),
+    Tee(Option<PathBuf>),
+    Flush(mpsc::Sender<io::Result<()>>),
+    Stop,
+}
+
+/// the sending end of a nonblocking config's queue, standing in for its sinks
+#[derive(Debug)]
+pub(crate) struct Worker {
+    sender: SyncSender<Job>,
+    when_full: FullBehavior,
+    dropped: Arc<AtomicU64>,
+
+    /// the output the background thread writes to, written to directly once the thread has stopped
+    inner: Arc<Mutex<Output>>,
+}
+
+impl Worker {
+    fn inner(&self) -> std::sync::MutexGuard<'_, Output> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn has_terminal(&self) -> bool {
+        self.inner().has_terminal()
+    }
+
+    pub(crate) fn set_status(&self, status: Option<String>) -> io::Result<()> {
+        if let Err(mpsc::SendError(Job::Status(status))) = self.sender.send(Job::Status(status)) {
+            return self.inner().set_status(status);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn add(&self, sink: Sink) {
+        if let Err(mpsc::SendError(Job::Add(sink))) = self.sender.send(Job::Add(sink)) {
+            self.inner().add(sink);
+        }
+    }
+
+    pub(crate) fn set_tee(&self, path: Option<&Path>) {
+        if let Err(mpsc::SendError(Job::Tee(path))) =
+            self.sender.send(Job::Tee(path.map(Path::to_path_buf)))
+        {
+            self.inner().set_tee(path.as_deref());
+        }
+    }
+
+    pub(crate) fn write_line(
+        &self,
+        severity: &Severity,
+        line: &str,
+        buffered: bool,
+    ) -> io::Result<()> {
+        let job = Job::Line(severity.clone(), line.to_string(), buffered);
+        let sent = match self.when_full {
+            FullBehavior::Block => self.sender.send(job).is_ok(),
+            FullBehavior::Drop => match self.sender.try_send(job) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    true
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+            },
+        };
+
+        // the background thread has stopped, so write it here instead
+        if !sent {
+            return self.inner().write_line(severity, line, buffered);
+        }
+        Ok(())
+    }
+
+    /// wait for everything queued so far to be written, then flush the sinks
+    pub(crate) fn flush(&self) -> io::Result<()> {
+        let (done, wait) = mpsc::channel();
+        match self.sender.send(Job::Flush(done)) {
+            Ok(()) => wait.recv().unwrap_or(Ok(())),
+            Err(_) => self.inner().flush(),
+        }
+    }
+}
+
+/// the background thread, doing each job in order until told to stop. jobs queued by other threads after the stop
+/// are still done, so nothing sent before the queue is closed is lost
+fn run(output: &Mutex<Output>, receiver: Receiver<Job>) {
+    for job in receiver.iter() {
+        if !work(output, job) {
+            break;
+        }
+    }
+
+    for job in receiver.try_iter() {
+        work(output, job);
+    }
+    drop(receiver);
+
+    let _ = output
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .flush();
+}
+
+/// do `job`, returning whether to keep going
+fn work(output: &Mutex<Output>, job: Job) -> bool {
+    let mut output = output.lock().unwrap_or_else(PoisonError::into_inner);
+
+    match job {
+        Job::Line(severity, line, buffered) => {
+            let _ = output.write_line(&severity, &line, buffered);
+        }
+        Job::Status(status) => {
+            let _ = output.set_status(status);
+        }
+        Job::Add(sink) => output.add(sink),
+        Job::Tee(path) => output.set_tee(path.as_deref()),
+        Job::Flush(done) => {
+            let _ = done.send(output.flush());
+        }
+        Job::Stop => return false,
+    }
+    true
+}
+
+#[cfg(all(test, not(feature = "max_level_warn")))]
+mod test {
+    use super::*;
+    use crate::test::SharedBuffer;
+
+    #[test]
+    fn test_nonblocking() {
+        let (mut config, buffer) = crate::test::buffered_config();
+        config.set_buffered(true);
+
+        let (config, guard) = config.into_nonblocking();
+        for i in 0..1000 {
+            config.info(&format!("line {}", i));
+        }
+        config.flush();
+        assert_eq!(buffer.contents().lines().count(), 1000);
+
+        config.info("last");
+        drop(guard);
+        let lines: Vec<String> = buffer.contents().lines().map(str::to_string).collect();
+        assert_eq!(lines.len(), 1001);
+        assert!(lines
+            .iter()
+            .take(1000)
+            .enumerate()
+            .all(|(i, line)| line.ends_with(&format!("line {}", i))));
+        assert!(lines[1000].ends_with("last"));
+
+        // the thread has stopped, so this is written in this thread
+        config.info("after");
+        config.flush();
+        assert!(buffer.contents().ends_with("after\n"));
+    }
+
+    #[test]
+    fn test_nonblocking_drop() {
+        let buffer = SharedBuffer::default();
+        let mut config = Config::default();
+        let inner = Arc::new(Mutex::new(Output::new(Sink::new(buffer.clone(), false))));
+        config.output = inner.clone();
+
+        let (config, guard) = config.into_nonblocking_with(NonblockingOptions {
+            capacity: 1,
+            when_full: FullBehavior::Drop,
+        });
+
+        // the background thread can't write while the output is locked, so the queue fills up
+        let locked = inner.lock().unwrap();
+        for i in 0..10 {
+            config.info(&format!("line {}", i));
+        }
+        drop(locked);
+
+        config.flush();
+        let written = buffer.contents().lines().count() as u64;
+        assert!(guard.dropped() >= 8);
+        assert_eq!(written + guard.dropped(), 10);
+    }
+
+    #[test]
+    fn test_nonblocking_stop() {
+        let buffer = SharedBuffer::default();
+        let mut config = Config::default();
+        let inner = Arc::new(Mutex::new(Output::new(Sink::new(buffer.clone(), false))));
+        config.output = inner.clone();
+        let (config, guard) = config.into_nonblocking();
+
+        // the guard is dropped on another thread while the output is locked, so the line is queued after the stop
+        let locked = inner.lock().unwrap();
+        config.info("before");
+        let stopping = std::thread::spawn(move || drop(guard));
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        config.info("after");
+        drop(locked);
+
+        stopping.join().unwrap();
+        let contents = buffer.contents();
+        assert!(contents.contains("before\n"));
+        assert!(contents.contains("after\n"));
+    }
+}
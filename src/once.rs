@@ -0,0 +1,125 @@
+//! messages written only the first time they come up, or at most once in a while
+
+use std::{collections::HashMap, sync::PoisonError, time::Duration};
+
+use crate::{clock::Instant, Config, Severity};
+
+impl Config {
+    /// write a message the first time `key` is used, and do nothing every time after that, for as long as the process
+    /// runs. unlike `dedup_window` this never expires. keys are shared between clones, and are marked as used even if
+    /// the first message was filtered out
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::{Config, Severity};
+    /// let config = Config::default();
+    ///
+    /// for _ in 0..3 {
+    ///     // written once
+    ///     config.write_once("old-flag", Severity::Warning, "--old-flag is deprecated, use --new-flag");
+    /// }
+    /// ```
+    pub fn write_once(&self, key: &str, severity: Severity, message: &str) {
+        if self.due(key, None) {
+            self.write(severity, message);
+        }
+    }
+
+    /// write a warning the first time `key` is used, see `write_once`
+    pub fn warn_once(&self, key: &str, message: &str) {
+        self.write_once(key, Severity::Warning, message);
+    }
+
+    /// write a message if `key` has not been used in the last `interval`, and do nothing otherwise. keys are shared
+    /// with `write_once` and between clones, and are marked as used even if the message was filtered out
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::time::Duration;
+    /// use humantalk::{Config, Severity};
+    /// let config = Config::default();
+    ///
+    /// for _ in 0..1000 {
+    ///     // written once a minute at most
+    ///     config.write_throttled(Duration::from_secs(60), "disk", Severity::Warning, "disk nearly full");
+    /// }
+    /// ```
+    pub fn write_throttled(
+        &self,
+        interval: Duration,
+        key: &str,
+        severity: Severity,
+        message: &str,
+    ) {
+        if self.due(key, Some(interval)) {
+            self.write(severity, message);
+        }
+    }
+
+    /// write a warning if `key` has not been used in the last `interval`, see `write_throttled`
+    pub fn warn_every(&self, interval: Duration, key: &str, message: &str) {
+        self.write_throttled(interval, key, Severity::Warning, message);
+    }
+
+    /// whether `key` is unused, or was last used at least `interval` ago. `None` never expires. marks it as used now
+    /// if so
+    fn due(&self, key: &str, interval: Option<Duration>) -> bool {
+        let now = Instant::now();
+        let mut keys = self.once.lock().unwrap_or_else(PoisonError::into_inner);
+
+        match (keys.get(key), interval) {
+            (Some(_), None) => false,
+            (Some(last), Some(interval)) if now.duration_since(*last) < interval => false,
+            _ => {
+                keys.insert(key.to_string(), now);
+                true
+            }
+        }
+    }
+}
+
+/// when each key was last used with `Config::write_once` or `Config::write_throttled`
+pub(crate) type OnceKeys = HashMap<String, Instant>;
+
+#[cfg(all(test, not(feature = "max_level_error")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_write_once() {
+        let (config, buffer) = crate::test::buffered_config();
+
+        for _ in 0..3 {
+            config.write_once("deprecated", Severity::Warning, "old flag");
+            config
+                .clone()
+                .write_once("deprecated", Severity::Warning, "old flag");
+        }
+        config.write_once("other", Severity::Warning, "other flag");
+
+        assert_eq!(buffer.contents().matches("old flag").count(), 1);
+        assert_eq!(buffer.contents().matches("other flag").count(), 1);
+        assert_eq!(config.count(&Severity::Warning), 2);
+    }
+
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_write_throttled() {
+        let (config, buffer) = crate::test::buffered_config();
+
+        for _ in 0..5 {
+            config.warn_once("flag", "deprecated flag");
+            config.warn_every(Duration::from_secs(60), "disk", "disk nearly full");
+            config.write_throttled(Duration::ZERO, "tick", Severity::Info, "tick");
+        }
+        assert_eq!(buffer.contents().matches("deprecated flag").count(), 1);
+        assert_eq!(buffer.contents().matches("disk nearly full").count(), 1);
+        assert_eq!(buffer.contents().matches("tick").count(), 5);
+
+        config.warn_every(Duration::from_millis(20), "cache", "cache cold");
+        config.warn_every(Duration::from_millis(20), "cache", "cache cold");
+        std::thread::sleep(Duration::from_millis(30));
+        config.warn_every(Duration::from_millis(20), "cache", "cache cold");
+        assert_eq!(buffer.contents().matches("cache cold").count(), 2);
+    }
+}
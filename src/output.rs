@@ -0,0 +1,412 @@
+//! where styled messages are written to
+
+use std::{
+    fs::OpenOptions,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{nonblocking::Worker, ColorSupport, Severity};
+
+/// a destination for log lines, such as the terminal or a file, added to a config with `Config::add_sink`. each sink
+/// decides whether it gets colors, and how many, and can ignore messages below its own minimum severity
+///
+/// # Examples
+/// ```rust,no_run
+/// use humantalk::{Config, Severity, Sink};
+/// let config = Config::default(); // writes to stdout, in color
+///
+/// let mut file = Sink::file("app.log").unwrap(); // plain text, appended to app.log
+/// file.set_min_severity(Severity::Info);
+/// config.add_sink(file);
+///
+/// config.info("written to the terminal and app.log");
+/// ```
+pub struct Sink {
+    writer: BufWriter<Box<dyn Write + Send>>,
+    color: ColorSupport,
+    closed: bool,
+    terminal: bool,
+
+    /// the least severe level written to this sink, on top of the config's own filtering. `None` writes everything
+    pub min_severity: Option<Severity>,
+
+    /// where lines are recorded instead of written, for a sink made by `CaptureSink::sink`
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) capture: Option<crate::testing::CaptureSink>,
+}
+
+impl Sink {
+    /// a sink writing to `writer`, with the full 256-color palette if `color` is set
+    pub fn new(writer: impl Write + Send + 'static, color: bool) -> Sink {
+        Sink {
+            writer: BufWriter::new(Box::new(writer)),
+            color: if color {
+                ColorSupport::Ansi256
+            } else {
+                ColorSupport::None
+            },
+            closed: false,
+            terminal: false,
+            min_severity: None,
+            #[cfg(any(test, feature = "testing"))]
+            capture: None,
+        }
+    }
+
+    /// a sink writing to stdout, colored unless colors are disabled (`NO_COLOR`, `TERM=dumb`, or stdout is not a
+    /// terminal), and limited to the basic 16 colors on terminals without 256. see `ColorSupport::detect`. this is
+    /// where a config writes by default
+    pub fn stdout() -> Sink {
+        let mut sink = Sink::new(io::stdout(), false);
+        sink.color = ColorSupport::detect();
+        sink.terminal = console::Term::stdout().is_term();
+        sink
+    }
+
+    /// a sink appending plain text to the file at `path`, which is created if needed
+    pub fn file(path: impl AsRef<Path>) -> io::Result<Sink> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Sink::new(file, false))
+    }
+
+    /// set the colors this sink can show, converting lines to fit as they are written
+    pub fn set_color_support(&mut self, support: ColorSupport) {
+        self.color = support;
+    }
+
+    /// only write messages at least as severe as `severity` to this sink
+    pub fn set_min_severity(&mut self, severity: Severity) {
+        self.min_severity = Some(severity);
+    }
+
+    fn accepts(&self, severity: &Severity) -> bool {
+        match &self.min_severity {
+            _ if self.closed => false,
+            Some(min_severity) => severity.at_least(min_severity),
+            None => true,
+        }
+    }
+}
+
+impl Sink {
+    /// `text`, with its styling converted to the colors this sink can show, or stripped if it has none
+    fn styled<'a>(&self, text: &'a str) -> std::borrow::Cow<'a, str> {
+        self.color.convert(text)
+    }
+
+    /// close the sink if `result` is a broken pipe, which is not treated as an error
+    fn check(&mut self, result: io::Result<()>) -> io::Result<()> {
+        match result {
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {
+                self.closed = true;
+                Ok(())
+            }
+            result => result,
+        }
+    }
+}
+
+impl std::fmt::Debug for Sink {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Sink")
+            .field("color", &self.color)
+            .field("min_severity", &self.min_severity)
+            .field("closed", &self.closed)
+            .field("terminal", &self.terminal)
+            .field("buffered_bytes", &self.writer.buffer().len())
+            .finish()
+    }
+}
+
+/// the sinks log lines are written to, stdout by default. any buffered output is flushed when it is dropped
+#[derive(Debug)]
+pub(crate) struct Output {
+    sinks: Vec<Sink>,
+
+    /// a line kept at the bottom of terminal sinks, such as a progress bar, redrawn below each line written
+    status: Option<String>,
+
+    /// the queue of a nonblocking config, which everything is handed to instead of the sinks
+    worker: Option<Worker>,
+
+    /// the config's `tee_file`, and a sink appending to it, or `None` if it could not be opened
+    tee_path: Option<PathBuf>,
+    tee: Option<Sink>,
+}
+
+/// return to the start of the line and clear it, removing the status line
+const CLEAR_LINE: &str = "\r\u{1b}[2K";
+
+impl Output {
+    pub(crate) fn new(sink: Sink) -> Self {
+        Output {
+            sinks: vec![sink],
+            status: None,
+            worker: None,
+            tee_path: None,
+            tee: None,
+        }
+    }
+
+    /// an output handing everything to `worker`'s background thread
+    pub(crate) fn nonblocking(worker: Worker) -> Self {
+        Output {
+            sinks: vec![],
+            status: None,
+            worker: Some(worker),
+            tee_path: None,
+            tee: None,
+        }
+    }
+
+    /// mirror everything to the file at `path` as well, or stop if `None`. the file is opened again only when `path`
+    /// changes, and nothing is mirrored if it can't be opened
+    pub(crate) fn set_tee(&mut self, path: Option<&Path>) {
+        if self.tee_path.as_deref() == path {
+            return;
+        }
+        self.tee_path = path.map(Path::to_path_buf);
+
+        match &self.worker {
+            Some(worker) => worker.set_tee(path),
+            None => {
+                if let Some(tee) = &mut self.tee {
+                    let _ = tee.writer.flush();
+                }
+                self.tee = path.and_then(|path| Sink::file(path).ok());
+            }
+        }
+    }
+
+    /// whether any sink is an interactive terminal that a status line can be drawn on
+    pub(crate) fn has_terminal(&self) -> bool {
+        if let Some(worker) = &self.worker {
+            return worker.has_terminal();
+        }
+
+        self.sinks.iter().any(|sink| sink.terminal && !sink.closed)
+    }
+
+    /// draw `status` at the bottom of every terminal sink, replacing the current status line, or remove it if `None`
+    pub(crate) fn set_status(&mut self, status: Option<String>) -> io::Result<()> {
+        if let Some(worker) = &self.worker {
+            return worker.set_status(status);
+        }
+
+        let mut result = Ok(());
+
+        for sink in self
+            .sinks
+            .iter_mut()
+            .filter(|sink| sink.terminal && !sink.closed)
+        {
+            let status = sink.styled(status.as_deref().unwrap_or(""));
+            let written =
+                write!(sink.writer, "{}{}", CLEAR_LINE, status).and_then(|_| sink.writer.flush());
+            result = result.and(sink.check(written));
+        }
+
+        self.status = status;
+        result
+    }
+
+    pub(crate) fn add(&mut self, sink: Sink) {
+        if let Some(worker) = &self.worker {
+            return worker.add(sink);
+        }
+
+        self.sinks.push(sink);
+    }
+
+    /// write a single line of `severity` to every sink that accepts it, flushing straight away unless `buffered`.
+    /// `line` is always styled, and the styling is stripped for sinks without color. on terminals with a status line,
+    /// the line is written above it. every sink is written to even if
+    /// one fails, and the first error is returned. a sink whose reader has gone away, such as stdout piped into `head`,
+    /// is closed quietly rather than failing every write after it
+    pub(crate) fn write_line(
+        &mut self,
+        severity: &Severity,
+        line: &str,
+        buffered: bool,
+    ) -> io::Result<()> {
+        if let Some(worker) = &self.worker {
+            return worker.write_line(severity, line, buffered);
+        }
+
+        let mut result = Ok(());
+
+        let status = self.status.as_deref();
+        for sink in self
+            .sinks
+            .iter_mut()
+            .chain(self.tee.as_mut())
+            .filter(|sink| sink.accepts(severity))
+        {
+            #[cfg(any(test, feature = "testing"))]
+            if let Some(capture) = &sink.capture {
+                capture.record(severity, line);
+                continue;
+            }
+
+            // the line goes where the status line was, which is then drawn again below it
+            let status = status.filter(|_| sink.terminal);
+            if status.is_some() {
+                let _ = write!(sink.writer, "{}", CLEAR_LINE);
+            }
+
+            let written = writeln!(sink.writer, "{}", sink.styled(line));
+            let written = match status {
+                Some(status) => written
+                    .and_then(|_| write!(sink.writer, "{}", sink.styled(status)))
+                    .and_then(|_| sink.writer.flush()),
+                None => written.and_then(|_| {
+                    if buffered {
+                        Ok(())
+                    } else {
+                        sink.writer.flush()
+                    }
+                }),
+            };
+            result = result.and(sink.check(written));
+        }
+
+        result
+    }
+
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        if let Some(worker) = &self.worker {
+            return worker.flush();
+        }
+
+        let mut result = Ok(());
+
+        for sink in self.sinks.iter_mut().chain(self.tee.as_mut()) {
+            let flushed = sink.writer.flush();
+            result = result.and(sink.check(flushed));
+        }
+
+        result
+    }
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Output::new(Sink::stdout())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(not(feature = "max_level_warn"))]
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::test::SharedBuffer;
+    #[cfg(not(feature = "max_level_warn"))]
+    use crate::Config;
+
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_sinks() {
+        let terminal = SharedBuffer::default();
+        let file = SharedBuffer::default();
+
+        let mut config = Config::default();
+        config.output = Arc::new(Mutex::new(Output::new(Sink::new(terminal.clone(), true))));
+
+        let mut sink = Sink::new(file.clone(), false);
+        sink.set_min_severity(Severity::Warning);
+        config.add_sink(sink);
+
+        config.info("starting");
+        config.error("failed");
+
+        let raw =
+            |buffer: &SharedBuffer| String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(raw(&terminal).contains("\u{1b}["));
+        assert!(terminal.contents().contains("[info] starting"));
+        assert!(terminal.contents().contains("[error] failed"));
+
+        assert!(!raw(&file).contains('\u{1b}'));
+        assert_eq!(raw(&file).lines().count(), 1);
+        assert!(raw(&file).ends_with("[error] failed\n"));
+    }
+
+    /// a writer whose reader has gone away
+    #[cfg(not(feature = "max_level_warn"))]
+    struct ClosedPipe;
+
+    #[cfg(not(feature = "max_level_warn"))]
+    impl Write for ClosedPipe {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_broken_pipe() {
+        let file = SharedBuffer::default();
+        let mut output = Output::new(Sink::new(ClosedPipe, false));
+        output.add(Sink::new(file.clone(), false));
+
+        assert!(output.write_line(&Severity::Info, "first", false).is_ok());
+        assert!(output.sinks[0].closed);
+        assert!(output.write_line(&Severity::Info, "second", false).is_ok());
+        assert_eq!(file.contents(), "first\nsecond\n");
+
+        let config = Config::default();
+        let error = config
+            .write_to(&mut ClosedPipe, ColorSupport::None, Severity::Info, "lost")
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::BrokenPipe);
+
+        let mut buffer = vec![];
+        config
+            .write_to(&mut buffer, ColorSupport::None, Severity::Warning, "kept")
+            .unwrap();
+        let plain = String::from_utf8(buffer).unwrap();
+        assert!(!plain.contains('\u{1b}'));
+        assert!(plain.ends_with("[warning] kept\n"));
+
+        let mut buffer = vec![];
+        config
+            .write_to(
+                &mut buffer,
+                ColorSupport::Ansi256,
+                Severity::Warning,
+                "kept",
+            )
+            .unwrap();
+        assert!(String::from_utf8(buffer).unwrap().contains("\u{1b}[38;5;"));
+    }
+
+    #[test]
+    fn test_status_line() {
+        let terminal = SharedBuffer::default();
+        let file = SharedBuffer::default();
+
+        let mut sink = Sink::new(terminal.clone(), false);
+        sink.terminal = true;
+        let mut output = Output::new(sink);
+        output.add(Sink::new(file.clone(), false));
+        assert!(output.has_terminal());
+
+        output.set_status(Some("[####] 50%".to_string())).unwrap();
+        output
+            .write_line(&Severity::Info, "message", false)
+            .unwrap();
+        output.set_status(None).unwrap();
+
+        assert_eq!(
+            String::from_utf8(terminal.0.lock().unwrap().clone()).unwrap(),
+            format!("{0}[####] 50%{0}message\n[####] 50%{0}", CLEAR_LINE)
+        );
+        assert_eq!(file.contents(), "message\n");
+    }
+}
@@ -0,0 +1,71 @@
+//! reporting panics through humantalk's fatal error output
+
+use std::panic::PanicHookInfo;
+
+use crate::{Config, ExitBehavior};
+
+impl Config {
+    /// replace the panic hook, so panics are reported like `fatal_error`: the styled message, bug report guidance,
+    /// platform info and backtrace are printed and written to the crash report, and the process exits with code `3`.
+    /// with `ExitBehavior::Panic` the panic is left to unwind as usual once it has been reported
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// config.install_panic_hook();
+    /// let settings: Option<&str> = None;
+    /// settings.unwrap(); // reported as a crash
+    /// ```
+    pub fn install_panic_hook(&self) {
+        self.set_panic_hook(false);
+    }
+
+    /// like `install_panic_hook`, but the previously installed hook (by default rust's own `thread 'main' panicked`
+    /// message) still runs, after the crash has been reported
+    pub fn install_panic_hook_chained(&self) {
+        self.set_panic_hook(true);
+    }
+
+    fn set_panic_hook(&self, chain: bool) {
+        let config = self.clone();
+        let previous = chain.then(std::panic::take_hook);
+
+        std::panic::set_hook(Box::new(move |info| {
+            let report = config.build_crash_report(&panic_message(info));
+            let code = config.report_crash(&report);
+
+            if let Some(previous) = &previous {
+                previous(info);
+            }
+
+            // browser wasm targets cannot exit, and abort once the hook returns
+            if config.exit_behavior == ExitBehavior::Exit && !crate::clock::WASM {
+                std::process::exit(code);
+            }
+        }));
+    }
+}
+
+/// describe a panic the way rust's default hook does: `thread 'main' panicked at src/main.rs:2:5: message`
+fn panic_message(info: &PanicHookInfo) -> String {
+    let payload = info
+        .payload()
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| info.payload().downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>");
+
+    let thread = std::thread::current();
+    let location = info
+        .location()
+        .map_or(String::new(), |location| format!(" at {}", location));
+
+    format!(
+        "thread '{}' panicked{}: {}",
+        thread.name().unwrap_or("<unnamed>"),
+        location,
+        payload
+    )
+}
@@ -0,0 +1,212 @@
+//! progress bars that stay below log lines, styled to match humantalk's output
+
+use console::style;
+
+use crate::{ColorToColor256, Config, Severity};
+
+/// width of the bar itself, in characters
+const BAR_WIDTH: usize = 30;
+
+/// without a terminal, a plain line is written each time progress passes another multiple of this percentage
+const PLAIN_STEP: u64 = 10;
+
+/// a progress bar, created with `Config::progress`. on a terminal it is drawn on the last line, and messages written
+/// while it is active appear above it. otherwise a plain info line is written every 10%
+///
+/// # Examples
+/// ```rust
+/// use humantalk::Config;
+/// let config = Config::default();
+///
+/// let mut progress = config.progress(3, "downloading");
+/// for file in ["a.txt", "b.txt", "c.txt"] {
+///     progress.set_message(file);
+///     config.info(&format!("fetched {}", file)); // written above the bar
+///     progress.inc(1);
+/// }
+/// progress.finish();
+/// ```
+pub struct ProgressHandle {
+    config: Config,
+    label: String,
+    message: String,
+    position: u64,
+    total: u64,
+    terminal: bool,
+    last_step: u64,
+    finished: bool,
+}
+
+impl ProgressHandle {
+    /// advance the bar by `n`, up to its total
+    pub fn inc(&mut self, n: u64) {
+        self.position = self.position.saturating_add(n).min(self.total);
+        self.update();
+    }
+
+    /// show `message` after the bar, such as the item being worked on
+    pub fn set_message(&mut self, message: &str) {
+        self.message = message.to_string();
+
+        if self.terminal {
+            self.draw();
+        }
+    }
+
+    /// remove the bar and write a final info line in its place
+    pub fn finish(mut self) {
+        self.clear();
+        self.config.info(&format!(
+            "{}: done ({}/{})",
+            self.label, self.position, self.total
+        ));
+    }
+
+    fn update(&mut self) {
+        if self.terminal {
+            self.draw();
+            return;
+        }
+
+        let step = percent(self.position, self.total) / PLAIN_STEP;
+        if step > self.last_step {
+            self.last_step = step;
+            self.config
+                .info(&render_plain(&self.label, self.position, self.total));
+        }
+    }
+
+    fn draw(&self) {
+        let line = style(render_bar(
+            &self.label,
+            self.position,
+            self.total,
+            &self.message,
+        ))
+        .color256(self.config.get_color(&Severity::Info).to_color256())
+        .force_styling(true);
+
+        let _ = self.config.lock_output().set_status(Some(format!(
+            "{}{}",
+            self.config.indentation(),
+            line
+        )));
+    }
+
+    fn clear(&mut self) {
+        if self.terminal && !self.finished {
+            let _ = self.config.lock_output().set_status(None);
+        }
+
+        self.finished = true;
+    }
+}
+
+impl Drop for ProgressHandle {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl Config {
+    /// start a progress bar counting up to `total`, labelled with `label` and styled with the info color. nothing is
+    /// shown if info messages are suppressed
+    pub fn progress(&self, total: u64, label: &str) -> ProgressHandle {
+        let shown = self.should_write(&Severity::Info);
+        let terminal = shown && self.lock_output().has_terminal();
+
+        let progress = ProgressHandle {
+            config: self.clone(),
+            label: label.to_string(),
+            message: String::new(),
+            position: 0,
+            total,
+            terminal,
+            last_step: 0,
+            finished: !shown,
+        };
+
+        if terminal {
+            progress.draw();
+        }
+
+        progress
+    }
+}
+
+/// how far `position` is through `total`, from 0 to 100. an empty total counts as complete
+fn percent(position: u64, total: u64) -> u64 {
+    match total {
+        0 => 100,
+        total => position.min(total) * 100 / total,
+    }
+}
+
+/// a single line like `label [██████░░░░] 60% message`
+fn render_bar(label: &str, position: u64, total: u64, message: &str) -> String {
+    let percent = percent(position, total);
+    let filled = (percent as usize * BAR_WIDTH) / 100;
+
+    let bar = format!(
+        "{} [{}{}] {:>3}%",
+        label,
+        "█".repeat(filled),
+        "░".repeat(BAR_WIDTH - filled),
+        percent
+    );
+
+    match message {
+        "" => bar,
+        message => format!("{} {}", bar, message),
+    }
+}
+
+/// a plain line like `label: 60% (6/10)`, for output that is not a terminal
+fn render_plain(label: &str, position: u64, total: u64) -> String {
+    format!(
+        "{}: {}% ({}/{})",
+        label,
+        percent(position, total),
+        position,
+        total
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_bar() {
+        assert_eq!(
+            render_bar("copy", 0, 10, ""),
+            format!("copy [{}]   0%", "░".repeat(30))
+        );
+        assert_eq!(
+            render_bar("copy", 5, 10, "b.txt"),
+            format!("copy [{}{}]  50% b.txt", "█".repeat(15), "░".repeat(15))
+        );
+        assert_eq!(
+            render_bar("copy", 0, 0, ""),
+            format!("copy [{}] 100%", "█".repeat(30))
+        );
+    }
+
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_progress_plain() {
+        let (config, buffer) = crate::test::buffered_config();
+
+        let mut progress = config.progress(20, "copy");
+        for _ in 0..20 {
+            progress.inc(1);
+        }
+        progress.finish();
+
+        let contents = buffer.contents();
+        assert_eq!(contents.lines().count(), 11);
+        assert!(contents.contains("[info] copy: 10% (2/20)\n"));
+        assert!(contents.contains("[info] copy: 100% (20/20)\n"));
+        assert!(contents.ends_with("[info] copy: done (20/20)\n"));
+    }
+}
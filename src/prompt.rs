@@ -0,0 +1,387 @@
+//! interactive prompts, styled to match humantalk's log lines
+
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use console::{style, Color, Term};
+
+use crate::{ColorToColor256, Config, Severity};
+
+/// shown in place of a secret once it has been entered. always the same length, so it does not give away the secret's length
+const SECRET_MASK: &str = "********";
+
+impl Config {
+    /// ask a yes/no question, styled with the prompt color, re-prompting until the answer is `y`, `yes`, `n` or `no`.
+    /// when stdin is not a terminal nothing is asked and an error is returned, since there is no default to fall back
+    /// on. with `set_assume_yes` the answer is always yes
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// if config.confirm("Delete 132 files?").unwrap() {
+    ///     config.info("deleting");
+    /// }
+    /// ```
+    pub fn confirm(&self, question: &str) -> io::Result<bool> {
+        self.confirm_stdin(question, None)
+    }
+
+    /// like `confirm`, but just pressing enter answers `default`. when stdin is not a terminal, `default` is returned
+    /// without asking
+    pub fn confirm_or(&self, question: &str, default: bool) -> io::Result<bool> {
+        self.confirm_stdin(question, Some(default))
+    }
+
+    /// answer yes to every `confirm` without asking, for `--yes` flags
+    pub fn set_assume_yes(&mut self, assume_yes: bool) {
+        self.assume_yes = assume_yes;
+    }
+
+    /// set the color questions are asked in, instead of the warning color
+    pub fn set_prompt_color(&mut self, color: Color) {
+        self.prompt_color = Some(color);
+    }
+
+    /// the color questions are asked in
+    pub(crate) fn prompt_color256(&self) -> u8 {
+        self.prompt_color
+            .unwrap_or_else(|| self.get_color(&Severity::Warning))
+            .to_color256()
+    }
+
+    /// write out buffered messages and clear the status line before asking a question, so neither ends up drawn over or
+    /// after it
+    pub(crate) fn clear_for_prompt(&self) {
+        self.flush();
+        let _ = self.lock_output().set_status(None);
+    }
+
+    fn confirm_stdin(&self, question: &str, default: Option<bool>) -> io::Result<bool> {
+        let stdin = io::stdin();
+        if !self.assume_yes && !stdin.is_terminal() {
+            return default.ok_or_else(no_answer);
+        }
+
+        self.clear_for_prompt();
+        self.confirm_with(question, default, &mut stdin.lock(), &mut io::stdout())
+    }
+
+    fn confirm_with(
+        &self,
+        question: &str,
+        default: Option<bool>,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> io::Result<bool> {
+        let choices = match default {
+            Some(true) => "[Y/n]",
+            Some(false) => "[y/N]",
+            None => "[y/n]",
+        };
+        let color = self.prompt_color256();
+
+        if self.assume_yes {
+            writeln!(
+                output,
+                "{} y",
+                style(format!("{} {}", question, choices)).color256(color)
+            )?;
+            return Ok(true);
+        }
+
+        loop {
+            write!(
+                output,
+                "{} ",
+                style(format!("{} {}", question, choices)).color256(color)
+            )?;
+            output.flush()?;
+
+            let mut answer = String::new();
+            if input.read_line(&mut answer)? == 0 {
+                return default.ok_or_else(no_answer);
+            }
+
+            match (answer.trim().to_lowercase().as_str(), default) {
+                ("y" | "yes", _) => return Ok(true),
+                ("n" | "no", _) => return Ok(false),
+                ("", Some(default)) => return Ok(default),
+                _ => writeln!(output, "please answer y or n")?,
+            }
+        }
+    }
+}
+
+impl Config {
+    /// ask for a line of text, styled with the prompt color, with `default` shown dimmed in brackets. just pressing
+    /// enter answers `default`, or an empty string if there is none. when stdin is not a terminal, `default` is
+    /// returned without asking, or an error if there is none
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// let server = config.prompt("Server address", Some("localhost")).unwrap();
+    /// ```
+    pub fn prompt(&self, label: &str, default: Option<&str>) -> io::Result<String> {
+        self.prompt_validated(label, default, |answer| Ok(answer.to_string()))
+    }
+
+    /// like `prompt`, but the answer is passed to `validate`. while it returns an error, the error is shown as a
+    /// warning and the question asked again, until `set_prompt_attempts` answers have been rejected. the default is
+    /// validated too
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// let port: u16 = config
+    ///     .prompt_validated("Port", Some("8080"), |answer| {
+    ///         answer.parse().map_err(|_| format!("{} is not a port number", answer))
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn prompt_validated<T>(
+        &self,
+        label: &str,
+        default: Option<&str>,
+        validate: impl Fn(&str) -> Result<T, String>,
+    ) -> io::Result<T> {
+        let stdin = io::stdin();
+        if !stdin.is_terminal() {
+            let default = default.ok_or_else(no_answer)?;
+            return validate(default).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e));
+        }
+
+        self.clear_for_prompt();
+        self.prompt_with(
+            label,
+            default,
+            validate,
+            &mut stdin.lock(),
+            &mut io::stdout(),
+        )
+    }
+
+    /// ask for a secret such as a password, without echoing what is typed. once answered, the prompt shows a fixed
+    /// mask like `API token: ********`. when stdin is not a terminal the line is read as it is. the answer is never
+    /// written to the recent log or any sink
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// let token = config.prompt_secret("API token").unwrap();
+    /// ```
+    pub fn prompt_secret(&self, label: &str) -> io::Result<String> {
+        self.clear_for_prompt();
+
+        let term = Term::stdout();
+        if !term.is_term() || !io::stdin().is_terminal() {
+            return self.secret_with(label, &mut io::stdin().lock(), &mut io::stdout());
+        }
+
+        let question = style(format!("{}:", label)).color256(self.prompt_color256());
+        term.write_str(&format!("{} ", question))?;
+        let secret = term.read_secure_line()?;
+
+        term.clear_last_lines(1)?;
+        term.write_line(&format!("{} {}", question, SECRET_MASK))?;
+
+        Ok(secret)
+    }
+
+    fn secret_with(
+        &self,
+        label: &str,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> io::Result<String> {
+        write!(
+            output,
+            "{} ",
+            style(format!("{}:", label)).color256(self.prompt_color256())
+        )?;
+        output.flush()?;
+
+        let mut secret = String::new();
+        if input.read_line(&mut secret)? == 0 {
+            writeln!(output)?;
+            return Err(no_answer());
+        }
+        writeln!(output, "{}", SECRET_MASK)?;
+
+        Ok(secret.trim_end_matches(['\r', '\n']).to_string())
+    }
+
+    /// set how many answers `prompt_validated` rejects before giving up with an error. 3 by default
+    pub fn set_prompt_attempts(&mut self, attempts: usize) {
+        self.prompt_attempts = attempts.max(1);
+    }
+
+    fn prompt_with<T>(
+        &self,
+        label: &str,
+        default: Option<&str>,
+        validate: impl Fn(&str) -> Result<T, String>,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> io::Result<T> {
+        let color = self.prompt_color256();
+        let hint = match default {
+            Some(default) => format!(" {}", style(format!("[{}]", default)).dim()),
+            None => String::new(),
+        };
+
+        let mut error = String::new();
+        for _ in 0..self.prompt_attempts {
+            write!(output, "{}{}: ", style(label).color256(color), hint)?;
+            output.flush()?;
+
+            let mut answer = String::new();
+            if input.read_line(&mut answer)? == 0 {
+                writeln!(output)?;
+                let default = default.ok_or_else(no_answer)?;
+                return validate(default)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e));
+            }
+
+            let answer = answer.trim_end_matches(['\r', '\n']);
+            let answer = match (answer, default) {
+                ("", Some(default)) => default,
+                (answer, _) => answer,
+            };
+
+            match validate(answer) {
+                Ok(value) => return Ok(value),
+                Err(e) => {
+                    writeln!(output, "{}", self.line(&Severity::Warning, &e, None))?;
+                    error = e;
+                }
+            }
+        }
+
+        Err(io::Error::new(io::ErrorKind::InvalidInput, error))
+    }
+}
+
+fn no_answer() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "no answer given")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{test::SharedBuffer, Sink};
+
+    fn answer(input: &str, default: Option<bool>) -> (io::Result<bool>, String) {
+        let mut output = vec![];
+        let result = Config::default().confirm_with(
+            "continue?",
+            default,
+            &mut input.as_bytes(),
+            &mut output,
+        );
+        (
+            result,
+            console::strip_ansi_codes(&String::from_utf8(output).unwrap()).to_string(),
+        )
+    }
+
+    #[test]
+    fn test_confirm() {
+        assert!(answer("y\n", None).0.unwrap());
+        assert!(!answer("No\n", None).0.unwrap());
+        assert!(answer("\n", Some(true)).0.unwrap());
+        assert!(!answer("", Some(false)).0.unwrap());
+        assert!(answer("", None).0.is_err());
+
+        let (result, output) = answer("maybe\n\nYES\n", None);
+        assert!(result.unwrap());
+        assert_eq!(output.matches("continue? [y/n]").count(), 3);
+        assert_eq!(output.matches("please answer y or n").count(), 2);
+    }
+
+    #[test]
+    fn test_assume_yes() {
+        let mut config = Config::default();
+        config.set_assume_yes(true);
+
+        let mut output = vec![];
+        assert!(config
+            .confirm_with("delete?", Some(false), &mut "n\n".as_bytes(), &mut output)
+            .unwrap());
+        assert_eq!(
+            console::strip_ansi_codes(&String::from_utf8(output).unwrap()),
+            "delete? [y/N] y\n"
+        );
+    }
+
+    #[test]
+    fn test_prompt() {
+        let config = Config::default();
+        let ask = |input: &str, default| {
+            let mut output = vec![];
+            let result = config.prompt_with(
+                "port",
+                default,
+                |answer| {
+                    answer
+                        .parse::<u16>()
+                        .map_err(|_| format!("{} is not a port", answer))
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            );
+            (
+                result,
+                console::strip_ansi_codes(&String::from_utf8(output).unwrap()).to_string(),
+            )
+        };
+
+        assert_eq!(ask("80\n", None).0.unwrap(), 80);
+        assert_eq!(ask("\n", Some("8080")).0.unwrap(), 8080);
+        assert_eq!(ask("", Some("8080")).0.unwrap(), 8080);
+        assert!(ask("", None).0.is_err());
+
+        let (result, output) = ask("http\n443\n", Some("8080"));
+        assert_eq!(result.unwrap(), 443);
+        assert_eq!(output.matches("port [8080]: ").count(), 2);
+        assert!(output.contains("[warning] http is not a port\n"));
+
+        let (result, output) = ask("a\nb\nc\nd\n", None);
+        assert_eq!(result.unwrap_err().to_string(), "c is not a port");
+        assert_eq!(output.matches("port: ").count(), 3);
+    }
+
+    #[test]
+    fn test_prompt_secret() {
+        let file = SharedBuffer::default();
+        let config = Config::default();
+        config.add_sink(Sink::new(file.clone(), false));
+
+        let mut output = vec![];
+        let secret = config.secret_with("API token", &mut "hunter2\n".as_bytes(), &mut output);
+        assert_eq!(secret.unwrap(), "hunter2");
+
+        let output = console::strip_ansi_codes(&String::from_utf8(output).unwrap()).to_string();
+        assert_eq!(output, "API token: ********\n");
+
+        config.info("after the prompt");
+        assert!(!file.contents().contains("hunter2"));
+        assert!(config
+            .recent
+            .lock()
+            .unwrap()
+            .entries()
+            .all(|entry| !entry.message.contains("hunter2")));
+
+        assert!(config
+            .secret_with("API token", &mut "".as_bytes(), &mut vec![])
+            .is_err());
+    }
+}
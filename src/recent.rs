@@ -0,0 +1,95 @@
+//! in-memory ring buffer of recently written messages, dumped into crash reports
+
+use std::collections::VecDeque;
+
+use thetime::{System, Time};
+
+use crate::{clock, Severity};
+
+/// number of messages kept by default
+pub const DEFAULT_RECENT_LOG_CAPACITY: usize = 100;
+
+/// a message as it was written, kept for the `[RECENT LOG]` section of crash reports
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    /// severity the message was written with
+    pub severity: Severity,
+
+    /// the message itself, without styling
+    pub message: String,
+
+    time: System,
+}
+
+impl LogEntry {
+    /// when the message was written, as `2024-05-02T10:31:22Z`
+    pub fn timestamp(&self) -> String {
+        self.time.strftime(crate::TIMESTAMP_FORMAT)
+    }
+}
+
+impl std::fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "({}) [{}] {}",
+            self.timestamp(),
+            self.severity,
+            self.message
+        )
+    }
+}
+
+/// the last `capacity` messages, oldest first. the buffer is allocated once up front, so recording a message costs only
+/// its own `String`
+#[derive(Debug)]
+pub(crate) struct RecentLog {
+    entries: VecDeque<LogEntry>,
+    capacity: usize,
+}
+
+impl RecentLog {
+    pub(crate) fn new(capacity: usize) -> Self {
+        RecentLog {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// record a message, dropping the oldest one if the buffer is full
+    pub(crate) fn push(&mut self, severity: &Severity, message: &str) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(LogEntry {
+            severity: severity.clone(),
+            time: clock::now(),
+            message: message.to_string(),
+        });
+    }
+
+    /// change how many messages are kept, dropping the oldest if there are now too many
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        while self.entries.len() > capacity {
+            self.entries.pop_front();
+        }
+
+        self.entries
+            .reserve(capacity.saturating_sub(self.entries.len()));
+        self.capacity = capacity;
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub(crate) fn entries(&self) -> impl Iterator<Item = &LogEntry> {
+        self.entries.iter()
+    }
+}
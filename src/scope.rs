@@ -0,0 +1,106 @@
+//! child configs whose messages are namespaced by a scope, like `network: connected`
+
+use std::borrow::Cow;
+
+use crate::Config;
+
+/// separates the scopes of nested child configs
+const SCOPE_SEPARATOR: char = '/';
+
+impl Config {
+    /// a child config whose messages are prefixed with `scope`, like `[info] network: connected`. scoping a scoped
+    /// config nests, giving `network/tls`. the scope is also available as `{scope}` in the prefix template, and is
+    /// listed in the context of crash reports.
+    ///
+    /// the child is a clone, so like any clone it shares sinks, counts, groups and the recent log with its parent.
+    /// settings such as colors are copied, and changing them on the child leaves the parent as it is
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::{Color, Config, Severity};
+    /// let config = Config::default();
+    ///
+    /// let mut network = config.scoped("network");
+    /// network.info("connected"); // [info] network: connected
+    /// network.scoped("tls").warning("certificate expires soon"); // [warning] network/tls: certificate expires soon
+    ///
+    /// network.set_color(Severity::Info, Color::Cyan); // only changes network's color
+    /// ```
+    pub fn scoped(&self, scope: &str) -> Config {
+        let mut child = self.clone();
+        child.scope = Some(match &self.scope {
+            Some(parent) => format!("{}{}{}", parent, SCOPE_SEPARATOR, scope),
+            None => scope.to_string(),
+        });
+        child
+    }
+
+    /// `message` with the config's scope before it, if it has one
+    pub(crate) fn scoped_message<'a>(&self, message: &'a str) -> Cow<'a, str> {
+        match &self.scope {
+            Some(scope) => format!("{}: {}", scope, message).into(),
+            None => message.into(),
+        }
+    }
+
+    /// the key/value pairs listed in a crash report's context: the scope, if any, then the fields from `with_field`,
+    /// then those from `set_crash_context`
+    pub(crate) fn report_context(&self) -> Vec<(String, String)> {
+        self.scope
+            .iter()
+            .map(|scope| ("scope".to_string(), scope.clone()))
+            .chain(self.fields.iter().cloned())
+            .chain(self.crash_context.iter().cloned())
+            .collect()
+    }
+}
+
+#[cfg(all(test, not(feature = "max_level_warn")))]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::{output::Output, test::SharedBuffer, Color, Severity, Sink};
+
+    #[test]
+    fn test_scoped() {
+        let terminal = SharedBuffer::default();
+        let file = SharedBuffer::default();
+        let mut config = Config::default();
+        config.output = Arc::new(Mutex::new(Output::new(Sink::new(terminal, false))));
+        config.add_sink(Sink::new(file.clone(), false));
+        config.set_crash_context("request", "41");
+
+        let mut network = config.scoped("network");
+        let tls = network.scoped("tls");
+        assert_eq!(tls.scope.as_deref(), Some("network/tls"));
+        assert_eq!(config.scope, None);
+
+        network.set_color(Severity::Info, Color::Cyan);
+        assert_eq!(config.get_color(&Severity::Info), Color::Green);
+
+        config.info("starting");
+        network.info("connected");
+        tls.warning("handshake\nretrying");
+
+        let mut templated = tls.clone();
+        templated.set_prefix_template("[{scope}] ");
+        templated.error("failed");
+
+        let contents = file.contents();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert!(lines[0].ends_with("[info] starting"));
+        assert!(lines[1].ends_with("[info] network: connected"));
+        assert!(lines[2].ends_with("[warning] network/tls: handshake"));
+        assert_eq!(lines[4], "[network/tls] failed");
+
+        assert_eq!(
+            tls.report_context(),
+            vec![
+                ("scope".to_string(), "network/tls".to_string()),
+                ("request".to_string(), "41".to_string())
+            ]
+        );
+        assert_eq!(config.report_context().len(), 1);
+    }
+}
@@ -0,0 +1,66 @@
+//! rules separating the phases of a long run
+
+use console::{measure_text_width, style, Term};
+
+use crate::{ColorToColor256, Config, Severity};
+
+/// width of a section rule when the terminal's width is unknown
+const FALLBACK_WIDTH: usize = 80;
+
+/// character rules are drawn with
+const RULE: &str = "─";
+
+impl Config {
+    /// print `title` within a rule spanning the terminal, like `── Building ──────────`, in the info color. the rule
+    /// is 80 columns wide when the terminal's width is unknown. nothing is printed if info messages are suppressed
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// config.section("Building");
+    /// config.info("compiling");
+    /// config.section("Testing");
+    /// ```
+    pub fn section(&self, title: &str) {
+        if !self.should_write(&Severity::Info) {
+            return;
+        }
+
+        let indentation = self.indentation();
+        let width = Term::stdout()
+            .size_checked()
+            .map_or(FALLBACK_WIDTH, |(_, columns)| columns as usize)
+            .saturating_sub(indentation.len());
+        let line = style(render_section(title, width))
+            .color256(self.get_color(&Severity::Info).to_color256())
+            .force_styling(true);
+
+        let _ = self.lock_output().write_line(
+            &Severity::Info,
+            &format!("{}{}", indentation, line),
+            self.buffers(&Severity::Info),
+        );
+    }
+}
+
+/// `title` within a rule `width` columns wide. the rule always extends at least two columns past a long title
+fn render_section(title: &str, width: usize) -> String {
+    let lead = format!("{} {} ", RULE.repeat(2), title);
+    let rest = width.saturating_sub(measure_text_width(&lead)).max(2);
+
+    format!("{}{}", lead, RULE.repeat(rest))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_section() {
+        assert_eq!(render_section("Build", 16), "── Build ───────");
+        assert_eq!(measure_text_width(&render_section("Build", 80)), 80);
+        assert_eq!(render_section("a long title", 8), "── a long title ──");
+    }
+}
@@ -0,0 +1,281 @@
+//! menus for choosing from a list, styled to match humantalk's prompts
+
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use console::{style, Key, Term};
+
+use crate::Config;
+
+impl Config {
+    /// ask the user to choose one of `items`, returning its index. on a terminal the choice is made with the arrow keys
+    /// and enter, otherwise the items are numbered and the number is read from stdin, re-prompting until it is in range
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// let profiles = ["dev", "staging", "prod"];
+    /// let choice = config.select("Choose a profile", &profiles).unwrap();
+    /// config.info(&format!("using {}", profiles[choice]));
+    /// ```
+    pub fn select(&self, question: &str, items: &[&str]) -> io::Result<usize> {
+        check_items(items)?;
+        self.clear_for_prompt();
+
+        let term = Term::stdout();
+        if term.is_term() && io::stdin().is_terminal() {
+            return self
+                .select_keys(&term, question, items, false)
+                .map(|chosen| chosen[0]);
+        }
+
+        self.select_numbered(question, items, &mut io::stdin().lock(), &mut io::stdout())
+    }
+
+    /// ask the user to choose any number of `items`, returning their indices in order. on a terminal, space toggles the
+    /// highlighted item and enter confirms, otherwise the numbers are read from stdin, separated by commas or spaces
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// let features = ["serde", "anyhow", "syslog"];
+    /// for i in config.multi_select("Enable features", &features).unwrap() {
+    ///     config.info(&format!("enabling {}", features[i]));
+    /// }
+    /// ```
+    pub fn multi_select(&self, question: &str, items: &[&str]) -> io::Result<Vec<usize>> {
+        check_items(items)?;
+        self.clear_for_prompt();
+
+        let term = Term::stdout();
+        if term.is_term() && io::stdin().is_terminal() {
+            return self.select_keys(&term, question, items, true);
+        }
+
+        self.multi_select_numbered(question, items, &mut io::stdin().lock(), &mut io::stdout())
+    }
+
+    /// the arrow key menu. `multiple` allows toggling several items with space
+    fn select_keys(
+        &self,
+        term: &Term,
+        question: &str,
+        items: &[&str],
+        multiple: bool,
+    ) -> io::Result<Vec<usize>> {
+        let color = self.prompt_color256();
+        let mut cursor = 0;
+        let mut chosen = vec![false; items.len()];
+
+        term.write_line(&style(question).color256(color).to_string())?;
+        term.hide_cursor()?;
+        let hidden = HiddenCursor(term);
+
+        let result = loop {
+            for (i, item) in items.iter().enumerate() {
+                let marker = match (multiple, chosen[i]) {
+                    (false, _) => "",
+                    (true, true) => "[x] ",
+                    (true, false) => "[ ] ",
+                };
+                let line = format!("{} {}{}", if i == cursor { ">" } else { " " }, marker, item);
+
+                if i == cursor {
+                    term.write_line(&style(line).color256(color).bold().to_string())?;
+                } else {
+                    term.write_line(&line)?;
+                }
+            }
+
+            let key = term.read_key();
+            term.clear_last_lines(items.len())?;
+
+            match key? {
+                Key::ArrowUp | Key::Char('k') => {
+                    cursor = cursor.checked_sub(1).unwrap_or(items.len() - 1)
+                }
+                Key::ArrowDown | Key::Char('j') | Key::Tab => cursor = (cursor + 1) % items.len(),
+                Key::Char(' ') if multiple => chosen[cursor] = !chosen[cursor],
+                Key::Enter if multiple => {
+                    break Ok((0..items.len())
+                        .filter(|i| chosen[*i])
+                        .collect::<Vec<usize>>());
+                }
+                Key::Enter => break Ok(vec![cursor]),
+                Key::Escape => {
+                    break Err(io::Error::new(
+                        io::ErrorKind::Interrupted,
+                        "selection cancelled",
+                    ))
+                }
+                _ => {}
+            }
+        };
+
+        drop(hidden);
+        if let Ok(chosen) = &result {
+            let names: Vec<&str> = chosen.iter().map(|i| items[*i]).collect();
+            term.clear_last_lines(1)?;
+            term.write_line(&format!(
+                "{} {}",
+                style(question).color256(color),
+                names.join(", ")
+            ))?;
+        }
+
+        result
+    }
+
+    fn select_numbered(
+        &self,
+        question: &str,
+        items: &[&str],
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> io::Result<usize> {
+        loop {
+            let answer = self.ask_numbered(question, items, "", input, output)?;
+
+            match parse_choice(answer.trim(), items.len()) {
+                Some(choice) => return Ok(choice),
+                None => writeln!(output, "please enter a number from 1 to {}", items.len())?,
+            }
+        }
+    }
+
+    fn multi_select_numbered(
+        &self,
+        question: &str,
+        items: &[&str],
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> io::Result<Vec<usize>> {
+        loop {
+            let answer =
+                self.ask_numbered(question, items, ", separated by commas", input, output)?;
+
+            let choices: Option<Vec<usize>> = answer
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|part| !part.is_empty())
+                .map(|part| parse_choice(part, items.len()))
+                .collect();
+
+            match choices {
+                Some(mut choices) => {
+                    choices.sort_unstable();
+                    choices.dedup();
+                    return Ok(choices);
+                }
+                None => writeln!(output, "please enter numbers from 1 to {}", items.len())?,
+            }
+        }
+    }
+
+    /// print the numbered menu and read one line of answer
+    fn ask_numbered(
+        &self,
+        question: &str,
+        items: &[&str],
+        hint: &str,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> io::Result<String> {
+        let color = self.prompt_color256();
+
+        writeln!(output, "{}", style(question).color256(color))?;
+        for (i, item) in items.iter().enumerate() {
+            writeln!(output, "  {}) {}", style(i + 1).color256(color), item)?;
+        }
+        write!(
+            output,
+            "{} ",
+            style(format!("[1-{}{}]", items.len(), hint)).dim()
+        )?;
+        output.flush()?;
+
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "no answer given",
+            ));
+        }
+
+        Ok(answer)
+    }
+}
+
+/// shows the terminal's cursor again when dropped, so a menu that fails part way does not leave it hidden
+struct HiddenCursor<'a>(&'a Term);
+
+impl Drop for HiddenCursor<'_> {
+    fn drop(&mut self) {
+        let _ = self.0.show_cursor();
+    }
+}
+
+/// menus need something to choose from
+fn check_items(items: &[&str]) -> io::Result<()> {
+    if items.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "nothing to choose from",
+        ));
+    }
+
+    Ok(())
+}
+
+/// the index of the item numbered `answer`, counting from 1, if it is in range
+fn parse_choice(answer: &str, count: usize) -> Option<usize> {
+    match answer.parse::<usize>() {
+        Ok(choice) if (1..=count).contains(&choice) => Some(choice - 1),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_select_numbered() {
+        let config = Config::default();
+        let items = ["dev", "staging", "prod"];
+
+        let mut output = vec![];
+        let choice = config.select_numbered(
+            "profile?",
+            &items,
+            &mut "0\nprod\n3\n".as_bytes(),
+            &mut output,
+        );
+        assert_eq!(choice.unwrap(), 2);
+
+        let output = console::strip_ansi_codes(&String::from_utf8(output).unwrap()).to_string();
+        assert!(output.starts_with("profile?\n  1) dev\n  2) staging\n  3) prod\n[1-3] "));
+        assert_eq!(
+            output.matches("please enter a number from 1 to 3").count(),
+            2
+        );
+
+        assert!(config
+            .select_numbered("profile?", &items, &mut "".as_bytes(), &mut vec![])
+            .is_err());
+        assert!(config.select("profile?", &[]).is_err());
+
+        let choices = config.multi_select_numbered(
+            "which?",
+            &items,
+            &mut "4\n3, 1 3\n".as_bytes(),
+            &mut vec![],
+        );
+        assert_eq!(choices.unwrap(), vec![0, 2]);
+        let choices =
+            config.multi_select_numbered("which?", &items, &mut "\n".as_bytes(), &mut vec![]);
+        assert_eq!(choices.unwrap(), Vec::<usize>::new());
+    }
+}
@@ -0,0 +1,133 @@
+//! serde support for `Color`, which lives in the console crate. colors are written as their name (`"red"`) or, for
+//! `Color::Color256`, as the bare 256-color code. anything `parse_color` accepts can be read back, such as `"#ff8800"`
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use console::Color;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{parse_color, recent::RecentLog, Severity};
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum ColorRepr {
+    Name(String),
+    Code(u8),
+}
+
+impl From<&Color> for ColorRepr {
+    fn from(color: &Color) -> Self {
+        let name = match color {
+            Color::Black => "black",
+            Color::Red => "red",
+            Color::Green => "green",
+            Color::Yellow => "yellow",
+            Color::Blue => "blue",
+            Color::Magenta => "magenta",
+            Color::Cyan => "cyan",
+            Color::White => "white",
+            Color::Color256(code) => return ColorRepr::Code(*code),
+        };
+
+        ColorRepr::Name(name.to_string())
+    }
+}
+
+impl ColorRepr {
+    fn into_color<E: Error>(self) -> Result<Color, E> {
+        match self {
+            ColorRepr::Code(code) => Ok(Color::Color256(code)),
+            ColorRepr::Name(name) => parse_color(&name).map_err(E::custom),
+        }
+    }
+}
+
+pub(crate) fn serialize<S: Serializer>(
+    colors: &HashMap<Severity, Color>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    // sorted so the output is stable between runs
+    let mut entries: Vec<(&Severity, ColorRepr)> = colors
+        .iter()
+        .map(|(k, v)| (k, ColorRepr::from(v)))
+        .collect();
+    entries.sort_by_key(|(severity, _)| std::cmp::Reverse(*severity));
+
+    serializer.collect_map(entries)
+}
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<HashMap<Severity, Color>, D::Error> {
+    HashMap::<Severity, ColorRepr>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(severity, color)| Ok((severity, color.into_color()?)))
+        .collect()
+}
+
+/// a single optional color, written like the colors in the map
+pub(crate) mod option {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        color: &Option<Color>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        color.as_ref().map(ColorRepr::from).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Color>, D::Error> {
+        Option::<ColorRepr>::deserialize(deserializer)?
+            .map(ColorRepr::into_color)
+            .transpose()
+    }
+}
+
+/// the recent log is runtime state, so only its capacity is written out
+pub(crate) mod recent_log_capacity {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        recent: &Arc<Mutex<RecentLog>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(
+            recent
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .capacity() as u64,
+        )
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Arc<Mutex<RecentLog>>, D::Error> {
+        Ok(Arc::new(Mutex::new(RecentLog::new(usize::deserialize(
+            deserializer,
+        )?))))
+    }
+}
+
+/// maps keyed by severity, such as text attributes and labels, sorted like colors so the output is stable between runs
+pub(crate) mod sorted {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer, V: Serialize>(
+        map: &HashMap<Severity, V>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut entries: Vec<(&Severity, &V)> = map.iter().collect();
+        entries.sort_by_key(|(severity, _)| std::cmp::Reverse(*severity));
+
+        serializer.collect_map(entries)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>, V: Deserialize<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<Severity, V>, D::Error> {
+        HashMap::deserialize(deserializer)
+    }
+}
@@ -0,0 +1,134 @@
+//! writing errors along with the chain of errors that caused them
+
+use std::error::Error;
+
+use crate::{Config, Severity};
+
+/// the most causes followed from one error, so a cycle of `source()`s cannot loop forever
+const MAX_SOURCE_DEPTH: usize = 32;
+
+impl Config {
+    /// write `error` as an error, followed by each of its causes on its own `caused by:` line
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// if let Err(e) = std::fs::read_to_string("settings.toml") {
+    ///     config.error_with_source(&e);
+    /// }
+    /// ```
+    pub fn error_with_source(&self, error: &dyn Error) {
+        self.write_error(Severity::Error, error);
+    }
+
+    /// write `error` at `severity`, followed by each of its causes on its own `caused by:` line. the whole chain is a
+    /// single message, so it is kept together in the recent log of crash reports
+    pub fn write_error(&self, severity: Severity, error: &dyn Error) {
+        if !self.should_write(&severity) {
+            self.counts.record(&severity);
+            return;
+        }
+
+        self.write(severity, &error_chain(error));
+    }
+}
+
+/// `error`'s message, with each cause on a further `caused by:` line
+pub(crate) fn error_chain(error: &dyn Error) -> String {
+    let mut message = error.to_string();
+
+    for cause in error_causes(error) {
+        message.push_str(&format!("\n  caused by: {}", cause));
+    }
+
+    message
+}
+
+/// the messages of the errors that caused `error`, outermost first. after `MAX_SOURCE_DEPTH` causes the rest are
+/// replaced by a single `...`
+pub(crate) fn error_causes(error: &dyn Error) -> Vec<String> {
+    let mut causes = vec![];
+    let mut source = error.source();
+
+    while let Some(cause) = source {
+        if causes.len() == MAX_SOURCE_DEPTH {
+            causes.push("...".to_string());
+            break;
+        }
+
+        causes.push(cause.to_string());
+        source = cause.source();
+    }
+
+    causes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Layer(&'static str, Option<Box<Layer>>);
+
+    impl std::fmt::Display for Layer {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl Error for Layer {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            self.1
+                .as_deref()
+                .map(|layer| layer as &(dyn Error + 'static))
+        }
+    }
+
+    #[derive(Debug)]
+    struct Cycle;
+
+    impl std::fmt::Display for Cycle {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "cycle")
+        }
+    }
+
+    impl Error for Cycle {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&Cycle)
+        }
+    }
+
+    #[test]
+    fn test_error_with_source() {
+        let error = Layer(
+            "could not sync",
+            Some(Box::new(Layer(
+                "request failed",
+                Some(Box::new(Layer("connection refused", None))),
+            ))),
+        );
+
+        let config = Config::default();
+        config.start_capture();
+        config.error_with_source(&error);
+
+        assert_eq!(
+            config.take_captured(),
+            vec![(
+                Severity::Error,
+                "could not sync\n  caused by: request failed\n  caused by: connection refused"
+                    .to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_error_chain_cycle() {
+        let chain = error_chain(&Cycle);
+        assert_eq!(chain.lines().count(), MAX_SOURCE_DEPTH + 2);
+        assert!(chain.ends_with("caused by: ..."));
+    }
+}
@@ -0,0 +1,236 @@
+//! spinner for operations that take a while, styled to match humantalk's log lines
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Condvar, Mutex, PoisonError,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use console::style;
+
+use crate::{ColorToColor256, Config, Severity};
+
+/// frames the spinner cycles through on each tick
+const FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// time between frames when the spinner animates itself
+const FRAME_INTERVAL: Duration = Duration::from_millis(80);
+
+/// a single-line spinner, created with `Config::spinner`. on a terminal with colors it animates on a background thread,
+/// and messages written while it spins appear above it. otherwise its label is written once as an info line
+///
+/// # Examples
+/// ```rust
+/// use humantalk::Config;
+/// let config = Config::default();
+///
+/// let spinner = config.spinner("Connecting to server…");
+/// spinner.set_message("Waiting for a response…");
+/// spinner.succeed("Connected");
+/// ```
+pub struct Spinner {
+    config: Config,
+    state: Arc<SpinnerState>,
+    thread: Option<JoinHandle<()>>,
+    enabled: bool,
+}
+
+/// what the spinner shows, shared with its background thread
+struct SpinnerState {
+    label: Mutex<String>,
+    frame: AtomicUsize,
+    stopped: Mutex<bool>,
+    wake: Condvar,
+}
+
+impl Spinner {
+    /// advance the spinner by one frame and redraw it, on top of the background animation
+    pub fn tick(&mut self) {
+        if !self.enabled {
+            return;
+        }
+
+        self.state.frame.fetch_add(1, Ordering::SeqCst);
+        draw(&self.config, &self.state);
+    }
+
+    /// whether the spinner is animated, rather than written as a single line or not at all
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// replace the text shown next to the spinner
+    pub fn set_message(&self, message: &str) {
+        *self
+            .state
+            .label
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = message.to_string();
+
+        if self.enabled {
+            draw(&self.config, &self.state);
+        }
+    }
+
+    /// remove the spinner and write `message` as an info line in its place
+    pub fn finish(mut self, message: &str) {
+        self.clear();
+        self.config.info(message);
+    }
+
+    /// remove the spinner and write `message` after a check mark, as an info line
+    pub fn succeed(mut self, message: &str) {
+        self.clear();
+        self.config.info(&format!("✔ {}", message));
+    }
+
+    /// remove the spinner and write `message` after a cross, as an error
+    pub fn fail(mut self, message: &str) {
+        self.clear();
+        self.config.error(&format!("✖ {}", message));
+    }
+
+    /// remove the spinner without writing anything
+    pub fn finish_and_clear(mut self) {
+        self.clear();
+    }
+
+    /// stop the background thread and remove the spinner's line
+    fn clear(&mut self) {
+        *self
+            .state
+            .stopped
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = true;
+        self.state.wake.notify_all();
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+
+        if self.enabled {
+            let _ = self.config.lock_output().set_status(None);
+            self.enabled = false;
+        }
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// draw the current frame and label as the output's status line. the output's lock keeps it from being drawn in the
+/// middle of a message
+fn draw(config: &Config, state: &SpinnerState) {
+    let frame = FRAMES[state.frame.load(Ordering::SeqCst) % FRAMES.len()];
+    let label = state
+        .label
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    let line = style(format!("{} {}", frame, label))
+        .color256(config.get_color(&Severity::Info).to_color256())
+        .force_styling(true);
+
+    let _ = config
+        .lock_output()
+        .set_status(Some(format!("{}{}", config.indentation(), line)));
+}
+
+impl Config {
+    /// start a spinner labelled with `label`, styled with the info color. it animates only when writing to a terminal
+    /// with colors enabled, and shows nothing when info messages are suppressed by quiet or silent mode
+    pub fn spinner(&self, label: &str) -> Spinner {
+        let shown = self.should_write(&Severity::Info);
+        let enabled = shown && console::colors_enabled() && self.lock_output().has_terminal();
+
+        let state = Arc::new(SpinnerState {
+            label: Mutex::new(label.to_string()),
+            frame: AtomicUsize::new(0),
+            stopped: Mutex::new(false),
+            wake: Condvar::new(),
+        });
+
+        let thread = if enabled {
+            draw(self, &state);
+
+            let config = self.clone();
+            let state = Arc::clone(&state);
+            Some(std::thread::spawn(move || animate(&config, &state)))
+        } else {
+            if shown {
+                self.info(label);
+            }
+
+            None
+        };
+
+        Spinner {
+            config: self.clone(),
+            state,
+            thread,
+            enabled,
+        }
+    }
+}
+
+/// redraw the spinner every frame until it is stopped
+fn animate(config: &Config, state: &SpinnerState) {
+    let mut stopped = state.stopped.lock().unwrap_or_else(PoisonError::into_inner);
+
+    loop {
+        stopped = state
+            .wake
+            .wait_timeout(stopped, FRAME_INTERVAL)
+            .unwrap_or_else(PoisonError::into_inner)
+            .0;
+
+        if *stopped {
+            return;
+        }
+
+        state.frame.fetch_add(1, Ordering::SeqCst);
+        draw(config, state);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_spinner_quiet() {
+        let mut config = Config::default();
+        config.set_quiet(true);
+
+        let mut spinner = config.spinner("working");
+        assert!(!spinner.is_enabled());
+        spinner.tick();
+        spinner.finish("done");
+    }
+
+    #[cfg(not(feature = "max_level_warn"))]
+    #[test]
+    fn test_spinner_static() {
+        let (config, buffer) = crate::test::buffered_config();
+
+        let spinner = config.spinner("connecting");
+        assert!(!spinner.is_enabled());
+        spinner.set_message("still connecting");
+        spinner.succeed("connected");
+
+        config.spinner("uploading").fail("upload failed");
+
+        let contents = buffer.contents();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].ends_with("[info] connecting"));
+        assert!(lines[1].ends_with("[info] ✔ connected"));
+        assert!(lines[3].ends_with("[error] ✖ upload failed"));
+    }
+}
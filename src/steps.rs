@@ -0,0 +1,75 @@
+//! numbered steps for ordered task lists, such as `[2/5] linking`
+
+use crate::Config;
+
+/// a counter for an ordered list of steps, created with `Config::steps`. each step is written as an info line
+/// numbered out of the total
+///
+/// # Examples
+/// ```rust
+/// use humantalk::Config;
+/// let config = Config::default();
+///
+/// let mut steps = config.steps(3);
+/// steps.step("resolving dependencies"); // [1/3] resolving dependencies
+/// steps.step("compiling");              // [2/3] compiling
+/// steps.step("linking");                // [3/3] linking
+/// steps.finish("built in 4.2s");        // ✔ built in 4.2s
+/// ```
+pub struct StepTracker {
+    config: Config,
+    current: usize,
+    total: usize,
+}
+
+impl StepTracker {
+    /// move on to the next step, writing `message` after its number
+    pub fn step(&mut self, message: &str) {
+        self.current += 1;
+        self.config
+            .info(&format!("[{}/{}] {}", self.current, self.total, message));
+    }
+
+    /// the number of the last step written, `0` before the first
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// end the steps, writing `message` after a check mark, as an info line
+    pub fn finish(self, message: &str) {
+        self.config.info(&format!("✔ {}", message));
+    }
+}
+
+impl Config {
+    /// start numbering `total` steps, see `StepTracker`
+    pub fn steps(&self, total: usize) -> StepTracker {
+        StepTracker {
+            config: self.clone(),
+            current: 0,
+            total,
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "max_level_warn")))]
+mod test {
+    #[test]
+    fn test_steps() {
+        let (config, buffer) = crate::test::buffered_config();
+
+        let mut steps = config.steps(2);
+        assert_eq!(steps.current(), 0);
+        steps.step("compiling");
+        steps.step("linking");
+        assert_eq!(steps.current(), 2);
+        steps.finish("built");
+
+        let contents = buffer.contents();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with("[info] [1/2] compiling"));
+        assert!(lines[1].ends_with("[info] [2/2] linking"));
+        assert!(lines[2].ends_with("[info] ✔ built"));
+    }
+}
@@ -0,0 +1,316 @@
+//! sending messages to the system log, behind the `syslog` feature: syslog on unix, and the Event Log on windows
+
+use std::io;
+#[cfg(unix)]
+use std::{os::unix::net::UnixDatagram, path::Path};
+
+use crate::{Config, Severity};
+
+/// sockets the local syslog daemon listens on, tried in order
+#[cfg(unix)]
+const SOCKETS: &[&str] = &["/dev/log", "/var/run/syslog", "/var/run/log"];
+
+/// syslog facility messages are logged under
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Facility {
+    /// `user`, for user-level programs
+    User,
+    /// `daemon`, for system daemons and services
+    Daemon,
+    /// `local0`, reserved for local use
+    Local0,
+    /// `local1`, reserved for local use
+    Local1,
+    /// `local2`, reserved for local use
+    Local2,
+    /// `local3`, reserved for local use
+    Local3,
+    /// `local4`, reserved for local use
+    Local4,
+    /// `local5`, reserved for local use
+    Local5,
+    /// `local6`, reserved for local use
+    Local6,
+    /// `local7`, reserved for local use
+    Local7,
+}
+
+impl Facility {
+    /// the facility's numeric code, as defined by RFC 5424
+    #[cfg(not(windows))]
+    fn code(&self) -> u8 {
+        match self {
+            Facility::User => 1,
+            Facility::Daemon => 3,
+            Facility::Local0 => 16,
+            Facility::Local1 => 17,
+            Facility::Local2 => 18,
+            Facility::Local3 => 19,
+            Facility::Local4 => 20,
+            Facility::Local5 => 21,
+            Facility::Local6 => 22,
+            Facility::Local7 => 23,
+        }
+    }
+}
+
+impl Severity {
+    /// the matching syslog priority: ERR, WARNING, INFO or DEBUG, which trace messages are sent as too
+    #[cfg(not(windows))]
+    fn syslog_priority(&self) -> u8 {
+        match self {
+            Severity::Error => 3,
+            Severity::Warning => 4,
+            Severity::Info => 6,
+            Severity::Debug | Severity::Trace => 7,
+        }
+    }
+
+    /// the matching Event Log type: error, warning, or information for info, debug and trace, which it has no level for
+    #[cfg(windows)]
+    fn event_type(&self) -> u16 {
+        use windows_sys::Win32::System::EventLog::{
+            EVENTLOG_ERROR_TYPE, EVENTLOG_INFORMATION_TYPE, EVENTLOG_WARNING_TYPE,
+        };
+
+        match self {
+            Severity::Error => EVENTLOG_ERROR_TYPE,
+            Severity::Warning => EVENTLOG_WARNING_TYPE,
+            Severity::Info | Severity::Debug | Severity::Trace => EVENTLOG_INFORMATION_TYPE,
+        }
+    }
+}
+
+/// a connection to the local syslog daemon, or on windows to the Event Log
+#[derive(Debug)]
+pub(crate) struct Syslog {
+    #[cfg(unix)]
+    socket: UnixDatagram,
+    /// the event source handle, kept as an integer so the connection can be shared between threads
+    #[cfg(windows)]
+    source: isize,
+    #[cfg(not(windows))]
+    facility: Facility,
+    #[cfg(not(windows))]
+    ident: String,
+
+    /// whether messages go only to the system log, rather than to the sinks as well
+    pub(crate) exclusive: bool,
+}
+
+/// the name messages are logged under: the executable's name, or `humantalk` if it is unknown
+fn ident() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| {
+            exe.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "humantalk".to_string())
+}
+
+impl Syslog {
+    #[cfg(unix)]
+    fn connect(facility: Facility) -> io::Result<Self> {
+        let mut last_error = io::Error::new(io::ErrorKind::NotFound, "no syslog socket found");
+
+        for path in SOCKETS {
+            match Syslog::connect_to(Path::new(path), facility) {
+                Ok(syslog) => return Ok(syslog),
+                Err(error) => last_error = error,
+            }
+        }
+
+        Err(last_error)
+    }
+
+    #[cfg(unix)]
+    fn connect_to(path: &Path, facility: Facility) -> io::Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(path)?;
+
+        Ok(Syslog {
+            socket,
+            facility,
+            ident: ident(),
+            exclusive: true,
+        })
+    }
+
+    #[cfg(windows)]
+    fn connect(_facility: Facility) -> io::Result<Self> {
+        use windows_sys::Win32::System::EventLog::RegisterEventSourceW;
+
+        let name = wide(&ident());
+        // SAFETY: `name` is a nul-terminated UTF-16 string that outlives the call
+        let source = unsafe { RegisterEventSourceW(std::ptr::null(), name.as_ptr()) };
+        if source.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Syslog {
+            source: source as isize,
+            exclusive: true,
+        })
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn connect(_facility: Facility) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the system log is only available on unix and windows",
+        ))
+    }
+
+    /// format a message the way local syslog daemons expect it: `<priority>ident[pid]: message`
+    #[cfg(not(windows))]
+    fn format(&self, severity: &Severity, message: &str) -> String {
+        let priority = self.facility.code() * 8 + severity.syslog_priority();
+        format!(
+            "<{}>{}[{}]: {}",
+            priority,
+            self.ident,
+            std::process::id(),
+            message
+        )
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn send(&self, severity: &Severity, message: &str) -> io::Result<()> {
+        self.socket
+            .send(self.format(severity, message).as_bytes())?;
+        Ok(())
+    }
+
+    /// report `message` as an event, which the Event Log stamps with the source name itself
+    #[cfg(windows)]
+    pub(crate) fn send(&self, severity: &Severity, message: &str) -> io::Result<()> {
+        use windows_sys::Win32::System::EventLog::ReportEventW;
+
+        let message = wide(message);
+        let strings = [message.as_ptr()];
+        // SAFETY: `source` is a handle from `RegisterEventSourceW` that is only closed on drop, and `strings` holds one
+        // nul-terminated UTF-16 string that outlives the call
+        let reported = unsafe {
+            ReportEventW(
+                self.source as _,
+                severity.event_type(),
+                0,
+                0,
+                std::ptr::null_mut(),
+                1,
+                0,
+                strings.as_ptr(),
+                std::ptr::null(),
+            )
+        };
+
+        match reported {
+            0 => Err(io::Error::last_os_error()),
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    pub(crate) fn send(&self, severity: &Severity, message: &str) -> io::Result<()> {
+        let _ = self.format(severity, message);
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+impl Drop for Syslog {
+    fn drop(&mut self) {
+        // SAFETY: the handle came from `RegisterEventSourceW` and is not used after this
+        unsafe { windows_sys::Win32::System::EventLog::DeregisterEventSource(self.source as _) };
+    }
+}
+
+/// `text` as a nul-terminated UTF-16 string, for the windows API
+#[cfg(windows)]
+fn wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+impl Config {
+    /// send messages to the system log under `facility`, instead of the terminal. messages are sent raw, without
+    /// timestamps, indentation or colors, since the journal adds its own. `fatal_error` still prints to the terminal.
+    ///
+    /// syslog is reached through its local socket (`/dev/log` on linux, `/var/run/syslog` on macOS). on windows,
+    /// messages go to the Application Event Log under the executable's name, and `facility` is ignored. if the system
+    /// log cannot be connected to, an error is returned and output is unchanged
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use humantalk::{Config, Facility};
+    /// let mut config = Config::default();
+    ///
+    /// if let Err(e) = config.log_to_syslog(Facility::Daemon) {
+    ///     config.warning(&format!("could not connect to syslog: {}", e));
+    /// }
+    /// config.info("service started");
+    /// ```
+    pub fn log_to_syslog(&mut self, facility: Facility) -> io::Result<()> {
+        self.syslog = Some(std::sync::Arc::new(Syslog::connect(facility)?));
+        Ok(())
+    }
+
+    /// send messages to the system log under `facility` like `log_to_syslog`, while still writing them to the
+    /// terminal and any other sinks, for services that are also run by hand
+    pub fn add_syslog(&mut self, facility: Facility) -> io::Result<()> {
+        let mut syslog = Syslog::connect(facility)?;
+        syslog.exclusive = false;
+
+        self.syslog = Some(std::sync::Arc::new(syslog));
+        Ok(())
+    }
+}
+
+#[cfg(all(test, unix, not(feature = "max_level_warn")))]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::{output::Output, test::SharedBuffer, Sink};
+
+    #[test]
+    fn test_syslog() {
+        let dir = crate::test::scratch_dir("syslog");
+        let path = dir.join("log.sock");
+        let daemon = UnixDatagram::bind(&path).unwrap();
+        // a message that never arrives fails the test rather than hanging it
+        daemon
+            .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+            .unwrap();
+
+        let mut config = Config::default();
+        config.syslog = Some(Arc::new(
+            Syslog::connect_to(&path, Facility::Local0).unwrap(),
+        ));
+        config.warning("disk nearly full");
+        config.info("checked disk");
+
+        let mut buf = [0; 256];
+        let len = daemon.recv(&mut buf).unwrap();
+        let received = String::from_utf8_lossy(&buf[..len]).into_owned();
+        assert!(received.starts_with("<132>"), "{}", received);
+        assert!(received.ends_with(&format!("[{}]: disk nearly full", std::process::id())));
+
+        let len = daemon.recv(&mut buf).unwrap();
+        assert!(String::from_utf8_lossy(&buf[..len]).starts_with("<134>"));
+
+        // alongside the sinks, as `add_syslog` connects
+        let buffer = SharedBuffer::default();
+        let mut syslog = Syslog::connect_to(&path, Facility::Local0).unwrap();
+        syslog.exclusive = false;
+        config.syslog = Some(Arc::new(syslog));
+        config.output = Arc::new(Mutex::new(Output::new(Sink::new(buffer.clone(), false))));
+        config.error("disk full");
+
+        let len = daemon.recv(&mut buf).unwrap();
+        assert!(String::from_utf8_lossy(&buf[..len]).ends_with("]: disk full"));
+        assert!(buffer.contents().ends_with("[error] disk full\n"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,160 @@
+//! aligned tables, with the header row styled to match humantalk's log lines
+
+use console::{measure_text_width, pad_str, style, Alignment};
+
+use crate::{ColorToColor256, Config, Severity};
+
+/// space placed between columns
+const COLUMN_GAP: &str = "  ";
+
+/// options for `Config::table_with`
+///
+/// # Examples
+/// ```rust
+/// use humantalk::{Config, TableOptions};
+/// let config = Config::default();
+///
+/// config.table_with(
+///     &["test", "status", "duration"],
+///     &[
+///         vec!["parse".to_string(), "ok".to_string(), "12ms".to_string()],
+///         vec!["render".to_string(), "failed".to_string(), "1.4s".to_string()],
+///     ],
+///     &TableOptions {
+///         right_align: vec![2],
+///     },
+/// );
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct TableOptions {
+    /// indices of the columns whose cells are right-aligned, rather than left-aligned
+    pub right_align: Vec<usize>,
+}
+
+impl Config {
+    /// print `rows` as a table under `headers`, with columns padded to line up. the header row uses the info color.
+    /// rows shorter than the header are padded with empty cells. nothing is printed if info messages are suppressed
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::Config;
+    /// let config = Config::default();
+    ///
+    /// config.table(
+    ///     &["name", "status"],
+    ///     &[vec!["api".to_string(), "running".to_string()]],
+    /// );
+    /// ```
+    pub fn table(&self, headers: &[&str], rows: &[Vec<String>]) {
+        self.table_with(headers, rows, &TableOptions::default());
+    }
+
+    /// print a table like `table`, aligning columns as set in `options`
+    pub fn table_with(&self, headers: &[&str], rows: &[Vec<String>], options: &TableOptions) {
+        if !self.should_write(&Severity::Info) {
+            return;
+        }
+
+        let color = self.get_color(&Severity::Info).to_color256();
+        let indentation = self.indentation();
+        let mut output = self.lock_output();
+
+        for (i, line) in render_table(headers, rows, options).into_iter().enumerate() {
+            let line = if i == 0 {
+                style(line)
+                    .color256(color)
+                    .bold()
+                    .force_styling(true)
+                    .to_string()
+            } else {
+                line
+            };
+
+            let _ = output.write_line(
+                &Severity::Info,
+                &format!("{}{}", indentation, line),
+                self.buffers(&Severity::Info),
+            );
+        }
+    }
+}
+
+/// lay out the header and rows as lines of unstyled text, without trailing whitespace
+fn render_table(headers: &[&str], rows: &[Vec<String>], options: &TableOptions) -> Vec<String> {
+    let columns = rows
+        .iter()
+        .map(Vec::len)
+        .chain([headers.len()])
+        .max()
+        .unwrap_or(0);
+    let mut widths = vec![0; columns];
+
+    let header: Vec<&str> = headers.to_vec();
+    let body: Vec<Vec<&str>> = rows
+        .iter()
+        .map(|row| row.iter().map(String::as_str).collect())
+        .collect();
+
+    for row in std::iter::once(&header).chain(&body) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(measure_text_width(cell));
+        }
+    }
+
+    std::iter::once(&header)
+        .chain(&body)
+        .map(|row| {
+            let cells: Vec<String> = widths
+                .iter()
+                .enumerate()
+                .map(|(i, width)| {
+                    let alignment = if options.right_align.contains(&i) {
+                        Alignment::Right
+                    } else {
+                        Alignment::Left
+                    };
+
+                    pad_str(row.get(i).copied().unwrap_or(""), *width, alignment, None).into_owned()
+                })
+                .collect();
+
+            cells.join(COLUMN_GAP).trim_end().to_string()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_table() {
+        let rows = vec![
+            vec!["parse".to_string(), "ok".to_string(), "12ms".to_string()],
+            vec![
+                "render".to_string(),
+                "failed".to_string(),
+                "1.4s".to_string(),
+            ],
+            vec!["lint".to_string()],
+        ];
+
+        let lines = render_table(
+            &["test", "status", "duration"],
+            &rows,
+            &TableOptions {
+                right_align: vec![2],
+            },
+        );
+
+        assert_eq!(
+            lines,
+            vec![
+                "test    status  duration",
+                "parse   ok          12ms",
+                "render  failed      1.4s",
+                "lint",
+            ]
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! the template each line's prefix is built from
+
+use crate::{Config, Severity};
+
+/// the prefix template used unless one is set, giving lines like `[error] boom`, or
+/// `(10:31:22) [error] boom` with `TimestampMode::Wallclock`
+pub const DEFAULT_PREFIX_TEMPLATE: &str = "{timestamp} [{severity}] ";
+
+impl Config {
+    /// set the template each line's prefix is built from. the placeholders are:
+    /// - `{severity}`: the severity's label, like `error`. `{severity:upper}` gives `ERROR`
+    /// - `{icon}`: a symbol for the severity, like `✖` for errors
+    /// - `{timestamp}`: the timestamp set with `set_timestamp_mode`, like `(10:31:22)`, or nothing by default
+    /// - `{app}`: the application's name and version set with `set_app_info`, or nothing
+    /// - `{scope}`: the scope of a config returned by `scoped`, like `network/tls`, or nothing. when the template has
+    ///   it, the scope is no longer written before the message
+    ///
+    /// anything else in braces is left as it is
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::Config;
+    /// let mut config = Config::default();
+    ///
+    /// config.set_prefix_template("{severity:upper} | ");
+    /// config.error("boom"); // ERROR | boom
+    /// ```
+    pub fn set_prefix_template(&mut self, template: &str) {
+        self.prefix_template = template.to_string();
+    }
+
+    /// set whether the severity tag is padded to the width of the longest label, so that messages line up:
+    /// ```text
+    /// [error]   boom
+    /// [warning] careful
+    /// ```
+    pub fn set_align_tags(&mut self, align_tags: bool) {
+        self.align_tags = align_tags;
+    }
+
+    /// the prefix for a line of `severity` written at `timestamp`, filled in from the prefix template
+    pub(crate) fn prefix(&self, severity: &Severity, timestamp: &str) -> String {
+        let mut prefix = String::new();
+        let mut rest = self.prefix_template.as_str();
+
+        while let Some(open) = rest.find('{') {
+            prefix.push_str(&rest[..open]);
+            rest = &rest[open..];
+
+            let Some(close) = rest.find('}') else {
+                break;
+            };
+
+            let placeholder = &rest[1..close];
+            match placeholder {
+                "severity" => prefix.push_str(&self.label(severity)),
+                "severity:upper" => prefix.push_str(&self.label(severity).to_uppercase()),
+                "icon" => prefix.push_str(icon(severity)),
+                "timestamp" => prefix.push_str(timestamp),
+                "app" => {
+                    if let Some(app) = &self.app_info {
+                        prefix.push_str(&format!("{} {}", app.name, app.version));
+                    }
+                }
+                "scope" => prefix.push_str(self.scope.as_deref().unwrap_or("")),
+                _ => prefix.push_str(&rest[..=close]),
+            }
+
+            rest = &rest[close + 1..];
+
+            // with no timestamp, the space separating it from the rest goes too
+            if placeholder == "timestamp" && timestamp.is_empty() {
+                rest = rest.strip_prefix(' ').unwrap_or(rest);
+            }
+        }
+
+        prefix.push_str(rest);
+
+        // the padding goes after the whole prefix, so brackets around the tag stay next to it
+        if self.align_tags && self.prefix_template.contains("{severity") {
+            let width = |severity: &Severity| console::measure_text_width(&self.label(severity));
+            let longest = Severity::ALL.iter().map(width).max().unwrap_or(0);
+            prefix.push_str(&" ".repeat(longest - width(severity)));
+        }
+
+        prefix
+    }
+}
+
+/// the symbol `{icon}` is replaced with
+fn icon(severity: &Severity) -> &'static str {
+    match severity {
+        Severity::Error => "✖",
+        Severity::Warning => "⚠",
+        Severity::Info => "ℹ",
+        Severity::Debug => "·",
+        Severity::Trace => "…",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_prefix_template() {
+        let mut config = Config::default();
+        assert_eq!(
+            config.prefix(&Severity::Error, "(10:31:22)"),
+            "(10:31:22) [error] "
+        );
+
+        config.set_prefix_template("{severity:upper} | ");
+        assert_eq!(
+            config.prefix(&Severity::Warning, "(10:31:22)"),
+            "WARNING | "
+        );
+
+        config.set_prefix_template("{icon} {severity}: ");
+        assert_eq!(config.prefix(&Severity::Error, "(10:31:22)"), "✖ error: ");
+
+        config.set_prefix_template("{unknown} {app}{severity");
+        assert_eq!(
+            config.prefix(&Severity::Info, "(10:31:22)"),
+            "{unknown} {severity"
+        );
+
+        config.set_prefix_template(DEFAULT_PREFIX_TEMPLATE);
+        config.set_align_tags(true);
+        assert_eq!(
+            config.prefix(&Severity::Error, "(10:31:22)"),
+            "(10:31:22) [error]   "
+        );
+        assert_eq!(
+            config.prefix(&Severity::Warning, "(10:31:22)"),
+            "(10:31:22) [warning] "
+        );
+        config.set_label(Severity::Info, "information");
+        assert_eq!(
+            config.prefix(&Severity::Warning, "(10:31:22)"),
+            "(10:31:22) [warning]     "
+        );
+        config.set_align_tags(false);
+
+        config.set_app_info(crate::app_info!());
+        config.set_prefix_template("[{app}] ");
+        assert_eq!(
+            config.prefix(&Severity::Info, "(10:31:22)"),
+            format!("[humantalk {}] ", env!("CARGO_PKG_VERSION"))
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! helpers for testing code that logs through humantalk, with the `testing` feature
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::{output::Output, Config, Severity, Sink};
+
+/// a sink recording each line written to it with its severity, instead of writing it anywhere. clones share the
+/// recording, so a test can keep one while the config writes to another. lines are recorded as they would be written,
+/// prefix included but without colors, like `[warning] disk nearly full`
+///
+/// # Examples
+/// ```rust
+/// use humantalk::{testing::CaptureSink, Config, Severity};
+/// let mut config = Config::default();
+/// let capture = CaptureSink::new();
+/// capture.install(&mut config);
+///
+/// config.error("disk full");
+/// capture.assert_logged(Severity::Error, "disk");
+/// assert!(capture.messages_of(Severity::Warning).is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CaptureSink(Arc<Mutex<Vec<(Severity, String)>>>);
+
+impl CaptureSink {
+    /// a capture sink that has recorded nothing yet
+    pub fn new() -> CaptureSink {
+        CaptureSink::default()
+    }
+
+    /// a sink recording into this capture, for `Config::add_sink`
+    pub fn sink(&self) -> Sink {
+        let mut sink = Sink::new(std::io::sink(), false);
+        sink.capture = Some(self.clone());
+        sink
+    }
+
+    /// make this capture the only sink of `config` and the configs cloned from it after this, so nothing is printed
+    pub fn install(&self, config: &mut Config) {
+        config.output = Arc::new(Mutex::new(Output::new(self.sink())));
+    }
+
+    /// every line recorded so far, oldest first
+    pub fn entries(&self) -> Vec<(Severity, String)> {
+        self.lock().clone()
+    }
+
+    /// the lines recorded at `severity`, oldest first
+    pub fn messages_of(&self, severity: Severity) -> Vec<String> {
+        self.lock()
+            .iter()
+            .filter(|(recorded, _)| *recorded == severity)
+            .map(|(_, line)| line.clone())
+            .collect()
+    }
+
+    /// panic, listing everything recorded, unless a line of `severity` containing `text` has been recorded
+    #[track_caller]
+    pub fn assert_logged(&self, severity: Severity, text: &str) {
+        let entries = self.lock();
+        if !entries
+            .iter()
+            .any(|(recorded, line)| *recorded == severity && line.contains(text))
+        {
+            let recorded: Vec<String> = entries
+                .iter()
+                .map(|(severity, line)| format!("  {}: {}", severity, line))
+                .collect();
+            panic!(
+                "no {} message containing {:?} was logged, the recorded lines are:\n{}",
+                severity,
+                text,
+                recorded.join("\n")
+            );
+        }
+    }
+
+    /// forget everything recorded so far
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// record a line written to a sink
+    pub(crate) fn record(&self, severity: &Severity, line: &str) {
+        self.lock().push((
+            severity.clone(),
+            console::strip_ansi_codes(line).to_string(),
+        ));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<(Severity, String)>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(all(test, not(feature = "max_level_error")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_capture_sink() {
+        let mut config = Config::default();
+        let capture = CaptureSink::new();
+        capture.install(&mut config);
+
+        config.warning("disk nearly full");
+        config.clone().error("upload failed");
+        capture.assert_logged(Severity::Warning, "disk");
+        capture.assert_logged(Severity::Error, "[error] upload failed");
+        assert_eq!(capture.messages_of(Severity::Error).len(), 1);
+        assert!(!capture.entries()[0].1.contains('\u{1b}'));
+
+        capture.clear();
+        assert!(capture.entries().is_empty());
+        let missing = std::panic::catch_unwind(|| capture.assert_logged(Severity::Warning, "disk"));
+        assert!(missing.is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! preset color schemes
+
+use std::collections::HashMap;
+
+use console::Color;
+
+use crate::{Config, Severity, StyleAttrs, DEFAULT_PREFIX_TEMPLATE};
+
+/// the default prefix with each severity's icon added, like `✖ [error] boom`
+const ICON_PREFIX_TEMPLATE: &str = "{timestamp} {icon} [{severity}] ";
+
+/// a preset set of colors for each severity, applied with `Config::with_theme` or `Config::set_theme`
+///
+/// # Examples
+/// ```rust
+/// use humantalk::{Config, Theme};
+/// let config = Config::with_theme(Theme::Solarized);
+///
+/// config.info("using the solarized palette");
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Theme {
+    /// red errors, yellow warnings, green info, blue debug and magenta trace messages
+    #[default]
+    Default,
+    /// the accent colors of the solarized palette
+    Solarized,
+    /// everything white, for terminals where color is unwanted
+    Monochrome,
+    /// the brightest 256-color shades, for dim or low contrast displays
+    HighContrast,
+    /// orange errors, purple warnings, blue info and grey debug and trace messages, which stay distinct with red-green color
+    /// blindness. each line is also prefixed with an icon for its severity, so it does not rely on color alone
+    ColorBlindSafe,
+    /// the colors and icons of `ColorBlindSafe`, with text attributes as a third distinction: errors are bold, warnings
+    /// underlined, info plain and debug and trace messages dim. used by `Config::default_accessible`
+    Accessible,
+}
+
+impl Theme {
+    /// the color this theme gives each severity
+    pub fn colors(&self) -> HashMap<Severity, Color> {
+        let [error, warning, info, debug, trace] = match self {
+            Theme::Default => [
+                Color::Red,
+                Color::Yellow,
+                Color::Green,
+                Color::Blue,
+                Color::Magenta,
+            ],
+            Theme::Solarized => [
+                Color::Color256(160),
+                Color::Color256(136),
+                Color::Color256(64),
+                Color::Color256(33),
+                Color::Color256(61),
+            ],
+            Theme::Monochrome => [Color::White; 5],
+            Theme::HighContrast => [
+                Color::Color256(196),
+                Color::Color256(226),
+                Color::Color256(46),
+                Color::Color256(51),
+                Color::Color256(201),
+            ],
+            Theme::ColorBlindSafe | Theme::Accessible => [
+                Color::Color256(208),
+                Color::Color256(141),
+                Color::Color256(39),
+                Color::Color256(245),
+                Color::Color256(240),
+            ],
+        };
+
+        HashMap::from([
+            (Severity::Error, error),
+            (Severity::Warning, warning),
+            (Severity::Info, info),
+            (Severity::Debug, debug),
+            (Severity::Trace, trace),
+        ])
+    }
+
+    /// the text attributes this theme gives each severity: bold errors, and with `Theme::Accessible` underlined
+    /// warnings and dim debug and trace messages as well
+    pub fn styles(&self) -> HashMap<Severity, StyleAttrs> {
+        match self {
+            Theme::Accessible => HashMap::from([
+                (Severity::Error, StyleAttrs::BOLD),
+                (
+                    Severity::Warning,
+                    StyleAttrs {
+                        underline: true,
+                        ..StyleAttrs::default()
+                    },
+                ),
+                (
+                    Severity::Debug,
+                    StyleAttrs {
+                        dim: true,
+                        ..StyleAttrs::default()
+                    },
+                ),
+                (
+                    Severity::Trace,
+                    StyleAttrs {
+                        dim: true,
+                        ..StyleAttrs::default()
+                    },
+                ),
+            ]),
+            _ => HashMap::from([(Severity::Error, StyleAttrs::BOLD)]),
+        }
+    }
+
+    /// the prefix template this theme uses, with an icon for each severity for the themes that don't rely on color
+    /// alone, and `DEFAULT_PREFIX_TEMPLATE` otherwise
+    pub fn prefix_template(&self) -> &'static str {
+        match self {
+            Theme::ColorBlindSafe | Theme::Accessible => ICON_PREFIX_TEMPLATE,
+            _ => DEFAULT_PREFIX_TEMPLATE,
+        }
+    }
+}
+
+impl Config {
+    /// create a default config using the colors of `theme`
+    pub fn with_theme(theme: Theme) -> Config {
+        let mut config = Config::default();
+        config.set_theme(theme);
+        config
+    }
+
+    /// create a default config that does not rely on telling red from green, using `Theme::Accessible`
+    pub fn default_accessible() -> Config {
+        Config::with_theme(Theme::Accessible)
+    }
+
+    /// replace every severity's color, text attributes and the prefix template with those of `theme`. themes that mark
+    /// severities with icons as well as color use a template with `{icon}`, and the rest go back to
+    /// `DEFAULT_PREFIX_TEMPLATE`. templates and text attributes set before this are replaced, so set them afterwards
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.colors = theme.colors();
+        self.styles = theme.styles();
+        self.prefix_template = theme.prefix_template().to_string();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_theme() {
+        let config = Config::with_theme(Theme::Monochrome);
+        assert!(config.colors.values().all(|color| *color == Color::White));
+        assert_eq!(config.colors.len(), 5);
+
+        assert_eq!(Config::default().colors, Theme::Default.colors());
+
+        let config = Config::with_theme(Theme::ColorBlindSafe);
+        assert_eq!(config.get_color(&Severity::Error), Color::Color256(208));
+        assert_eq!(
+            config.prefix(&Severity::Error, "(10:31:22)"),
+            "(10:31:22) ✖ [error] "
+        );
+
+        let config = Config::default_accessible();
+        assert_eq!(config.colors, Theme::ColorBlindSafe.colors());
+        assert!(config.get_style(&Severity::Warning).underline);
+        assert!(config.get_style(&Severity::Debug).dim);
+        assert_eq!(config.get_style(&Severity::Info), StyleAttrs::default());
+
+        // switching back drops the icons and attributes
+        let mut config = Config::default_accessible();
+        config.set_theme(Theme::Default);
+        assert_eq!(config.prefix_template, DEFAULT_PREFIX_TEMPLATE);
+        assert_eq!(config.styles, Config::default().styles);
+    }
+}
@@ -0,0 +1,73 @@
+//! the timestamp at the start of each line
+
+use thetime::Time;
+
+use crate::{clock, Config};
+
+/// what `{timestamp}` in the prefix template shows, set with `Config::set_timestamp_mode`
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum TimestampMode {
+    /// no timestamp. the space after `{timestamp}` in the template is left out too
+    #[default]
+    None,
+
+    /// the time of day, like `(10:31:22)`
+    Wallclock,
+
+    /// the time since the config was created, like `[+1.234s]`. clones measure from the same point
+    Elapsed,
+}
+
+impl Config {
+    /// set what the timestamp before each message shows
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::{Config, TimestampMode};
+    /// let mut config = Config::default(); // elapsed time is measured from here
+    ///
+    /// config.set_timestamp_mode(TimestampMode::Elapsed);
+    /// config.info("started"); // [+0.000s] [info] started
+    ///
+    /// config.set_timestamp_mode(TimestampMode::None);
+    /// config.info("done"); // [info] done
+    /// ```
+    pub fn set_timestamp_mode(&mut self, mode: TimestampMode) {
+        self.timestamp_mode = mode;
+    }
+
+    /// the timestamp for a line written now, or an empty string with `TimestampMode::None`
+    pub(crate) fn timestamp(&self) -> String {
+        match self.timestamp_mode {
+            TimestampMode::None => String::new(),
+            TimestampMode::Wallclock => format!("({})", clock::now().strftime("%H:%M:%S")),
+            TimestampMode::Elapsed => format!("[+{:.3}s]", self.start.elapsed().as_secs_f64()),
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "max_level_warn")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_timestamp_mode() {
+        let (mut config, buffer) = crate::test::buffered_config();
+        config.info("bare");
+        assert_eq!(buffer.contents(), "[info] bare\n");
+
+        config.set_timestamp_mode(TimestampMode::Wallclock);
+        let timestamp = config.timestamp();
+        assert_eq!(timestamp.len(), "(10:31:22)".len());
+        assert!(timestamp.starts_with('(') && timestamp.ends_with(')'));
+        assert_eq!(timestamp.matches(':').count(), 2);
+
+        config.set_timestamp_mode(TimestampMode::Elapsed);
+        assert!(config.timestamp().starts_with("[+0.0"));
+    }
+}
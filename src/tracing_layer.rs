@@ -0,0 +1,157 @@
+//! a `tracing_subscriber` layer that writes events through humantalk
+
+use std::fmt::Write;
+
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+use crate::{Config, Severity};
+
+/// a `tracing_subscriber` layer writing events as humantalk messages, styled and filtered by `config`. the spans an
+/// event happened in are shown before its message like `request{id=7}: `, and its other fields after it like
+/// ` user=ci`
+///
+/// # Examples
+/// ```rust
+/// use humantalk::Config;
+/// use tracing_subscriber::prelude::*;
+///
+/// let config = Config::default();
+/// tracing_subscriber::registry().with(config.tracing_layer()).init();
+///
+/// tracing::info!(user = "ci", "logged in");
+/// ```
+pub struct HumantalkLayer {
+    config: Config,
+}
+
+impl HumantalkLayer {
+    /// a layer writing events with `config`
+    pub fn new(config: Config) -> HumantalkLayer {
+        HumantalkLayer { config }
+    }
+}
+
+impl Config {
+    /// a `tracing_subscriber` layer writing events with a clone of this config, see `HumantalkLayer`
+    pub fn tracing_layer(&self) -> HumantalkLayer {
+        HumantalkLayer::new(self.clone())
+    }
+}
+
+/// the severity a `tracing` level is written with
+fn severity(level: &Level) -> Severity {
+    match *level {
+        Level::ERROR => Severity::Error,
+        Level::WARN => Severity::Warning,
+        Level::INFO => Severity::Info,
+        Level::DEBUG => Severity::Debug,
+        Level::TRACE => Severity::Trace,
+    }
+}
+
+/// collects an event's `message` field, and its other fields as ` key=value` pairs
+#[derive(Default)]
+struct Fields {
+    message: String,
+    rest: String,
+}
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            let _ = write!(self.rest, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            let _ = write!(self.rest, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+/// a span's name and fields, like `request{id=7}`, kept in the span's extensions
+struct SpanTag(String);
+
+impl<S> Layer<S> for HumantalkLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+
+        let tag = match fields.rest.trim_start() {
+            "" => span.name().to_string(),
+            rest => format!("{}{{{}}}", span.name(), rest),
+        };
+        span.extensions_mut().insert(SpanTag(tag));
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let severity = severity(event.metadata().level());
+        if !self.config.should_write(&severity) {
+            self.config.counts.record(&severity);
+            return;
+        }
+
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+
+        let mut message = String::new();
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                if let Some(tag) = span.extensions().get::<SpanTag>() {
+                    let _ = write!(message, "{}: ", tag.0);
+                }
+            }
+        }
+        message.push_str(&fields.message);
+        message.push_str(&fields.rest);
+
+        self.config.write(severity, &message);
+    }
+}
+
+#[cfg(all(test, not(feature = "max_level_error")))]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use tracing_subscriber::prelude::*;
+
+    use super::*;
+    use crate::{output::Output, test::SharedBuffer, Sink};
+
+    #[test]
+    fn test_tracing_layer() {
+        let (mut config, buffer) = crate::test::buffered_config();
+        config.set_min_severity(Severity::Info);
+
+        let subscriber = tracing_subscriber::registry().with(config.tracing_layer());
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("request", id = 7);
+            let _entered = span.enter();
+
+            tracing::warn!(user = "ci", "slow response");
+            tracing::trace!("hidden");
+        });
+
+        assert!(buffer
+            .contents()
+            .ends_with("[warning] request{id=7}: slow response user=ci\n"));
+        assert_eq!(buffer.contents().lines().count(), 1);
+        assert_eq!(config.count(&Severity::Trace), 1);
+    }
+}
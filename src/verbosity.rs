@@ -0,0 +1,73 @@
+//! mapping a count of `-v` flags to the least severe level shown
+
+use crate::{Config, Severity};
+
+impl Config {
+    /// a default config with its level set from a count of `-v` flags, see `set_verbosity`
+    pub fn with_verbosity(level: u8) -> Config {
+        let mut config = Config::default();
+        config.set_verbosity(level);
+        config
+    }
+
+    /// set `min_severity` from a count of `-v` flags, as clap-based tools do:
+    ///
+    /// | level | shown                              |
+    /// |-------|------------------------------------|
+    /// | 0     | errors and warnings                |
+    /// | 1     | info and above (`-v`)              |
+    /// | 2     | debug and above (`-vv`)            |
+    /// | 3+    | everything, including trace (`-vvv`) |
+    ///
+    /// # Examples
+    /// ```rust
+    /// use humantalk::Config;
+    /// let mut config = Config::default();
+    ///
+    /// let verbose_flags = 1; // for example from clap's `ArgAction::Count`
+    /// config.set_verbosity(verbose_flags);
+    /// config.info("shown with -v");
+    /// config.debug("hidden without -vv");
+    /// ```
+    pub fn set_verbosity(&mut self, level: u8) {
+        self.set_min_severity(verbosity_severity(level));
+    }
+}
+
+/// the least severe level shown at verbosity `level`
+fn verbosity_severity(level: u8) -> Severity {
+    match level {
+        0 => Severity::Warning,
+        1 => Severity::Info,
+        2 => Severity::Debug,
+        _ => Severity::Trace,
+    }
+}
+
+#[cfg(all(test, not(feature = "max_level_error")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_verbosity() {
+        assert_eq!(Config::with_verbosity(0).min_severity, Severity::Warning);
+        assert_eq!(Config::with_verbosity(1).min_severity, Severity::Info);
+        assert_eq!(Config::with_verbosity(2).min_severity, Severity::Debug);
+        assert_eq!(Config::with_verbosity(3).min_severity, Severity::Trace);
+        assert_eq!(
+            Config::with_verbosity(u8::MAX).min_severity,
+            Severity::Trace
+        );
+
+        let mut config = Config::with_verbosity(2);
+        // debug messages are hidden in release builds whatever the verbosity
+        assert_eq!(
+            config.should_write(&Severity::Debug),
+            cfg!(debug_assertions) && !cfg!(feature = "max_level_info")
+        );
+        assert!(!config.should_write(&Severity::Trace));
+        config.set_verbosity(0);
+        assert!(!config.should_write(&Severity::Info));
+        assert!(config.should_write(&Severity::Warning));
+    }
+}
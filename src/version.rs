@@ -0,0 +1,74 @@
+//! humantalk's own version and the optional features it was built with, for bug reports
+
+/// version of humantalk, taken from Cargo.toml at compile time
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// every optional feature, and whether this build has it enabled
+const FEATURES: &[(&str, bool)] = &[
+    ("anyhow", cfg!(feature = "anyhow")),
+    ("log", cfg!(feature = "log")),
+    ("max_level_debug", cfg!(feature = "max_level_debug")),
+    ("max_level_info", cfg!(feature = "max_level_info")),
+    ("max_level_warn", cfg!(feature = "max_level_warn")),
+    ("max_level_error", cfg!(feature = "max_level_error")),
+    ("serde", cfg!(feature = "serde")),
+    ("strip-debug", cfg!(feature = "strip-debug")),
+    ("syslog", cfg!(feature = "syslog")),
+    ("testing", cfg!(feature = "testing")),
+    ("tracing", cfg!(feature = "tracing")),
+];
+
+/// the version of humantalk, such as `0.1.2`
+pub fn version() -> &'static str {
+    VERSION
+}
+
+/// the version of humantalk along with its enabled optional features, such as `humantalk 0.1.2 (features: serde,
+/// syslog)`, or just `humantalk 0.1.2` if none are enabled. this is what crash reports end their platform info with
+///
+/// # Examples
+/// ```rust
+/// println!("{}", humantalk::version_info());
+/// ```
+pub fn version_info() -> String {
+    describe(VERSION, &enabled_features())
+}
+
+/// names of the optional features this build has enabled
+pub(crate) fn enabled_features() -> Vec<String> {
+    FEATURES
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// `humantalk {version}`, followed by `features` in parentheses if there are any
+pub(crate) fn describe(version: &str, features: &[String]) -> String {
+    if features.is_empty() {
+        format!("humantalk {}", version)
+    } else {
+        format!("humantalk {} (features: {})", version, features.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_version() {
+        assert_eq!(version(), env!("CARGO_PKG_VERSION"));
+        assert!(version_info().starts_with(&format!("humantalk {}", env!("CARGO_PKG_VERSION"))));
+        assert_eq!(
+            enabled_features().contains(&"serde".to_string()),
+            cfg!(feature = "serde")
+        );
+
+        assert_eq!(describe("1.0.0", &[]), "humantalk 1.0.0");
+        assert_eq!(
+            describe("1.0.0", &["serde".to_string(), "syslog".to_string()]),
+            "humantalk 1.0.0 (features: serde, syslog)"
+        );
+    }
+}
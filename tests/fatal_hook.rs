@@ -0,0 +1,43 @@
+//! runs `fatal_error` in a child process, since it exits the process
+
+use std::{path::PathBuf, process::Command};
+
+use humantalk::Config;
+
+const CHILD_ENV: &str = "HUMANTALK_FATAL_HOOK_DIR";
+
+#[test]
+fn fatal_hook_runs_before_exit() {
+    if let Ok(dir) = std::env::var(CHILD_ENV) {
+        let dir = PathBuf::from(dir);
+        let mut config = Config::default();
+        config.set_crash_report_path(dir.join("crash_report.log"));
+        config.on_fatal(|_| panic!("a failing hook must not stop the others"));
+        config.on_fatal(move |report| {
+            std::fs::write(dir.join("hook.txt"), &report.message).unwrap();
+        });
+
+        config.fatal_error("crashed in child");
+    }
+
+    let dir = std::env::temp_dir().join(format!("humantalk-fatal-hook-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let status = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "fatal_hook_runs_before_exit", "--nocapture"])
+        .env(CHILD_ENV, &dir)
+        .status()
+        .unwrap();
+
+    assert_eq!(status.code(), Some(3));
+    assert_eq!(
+        std::fs::read_to_string(dir.join("hook.txt")).unwrap(),
+        "crashed in child"
+    );
+    assert!(std::fs::read_to_string(dir.join("crash_report.log"))
+        .unwrap()
+        .contains("[FATAL] crashed in child"));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
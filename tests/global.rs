@@ -0,0 +1,39 @@
+//! the global config, in its own test binary since it is shared by the whole process
+#![cfg(not(feature = "max_level_warn"))]
+
+use humantalk::{global, set_global, Config, Severity};
+
+#[test]
+fn global_config_is_shared_between_threads() {
+    let config = Config::default();
+    config.start_capture();
+    set_global(config);
+
+    let threads: Vec<_> = (0..8)
+        .map(|thread| {
+            std::thread::spawn(move || {
+                for i in 0..50 {
+                    humantalk::info(&format!("thread {} message {}", thread, i));
+                    humantalk::htwarning!("thread {} warning {}", thread, i);
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    let old = global();
+    assert_eq!(old.count(&Severity::Info), 400);
+    assert_eq!(old.count(&Severity::Warning), 400);
+
+    // replacing the global config leaves the old one's state alone
+    set_global(Config::default());
+    assert_eq!(global().count(&Severity::Info), 0);
+
+    let captured = old.stop_capture();
+    assert_eq!(captured.len(), 800);
+    assert!(captured
+        .iter()
+        .all(|(_, message)| message.starts_with("thread ")));
+}
@@ -0,0 +1,77 @@
+//! panics in a child process with humantalk's panic hook installed, since the hook exits the process
+
+use std::{path::PathBuf, process::Command};
+
+use humantalk::Config;
+
+const CHILD_ENV: &str = "HUMANTALK_PANIC_HOOK_DIR";
+
+/// run `test` in a child process, returning its exit code, stderr and the crash report it wrote
+fn run_child(test: &str, name: &str) -> (Option<i32>, String, String) {
+    let dir = std::env::temp_dir().join(format!("humantalk-panic-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", test, "--nocapture", "--test-threads", "1"])
+        .env(CHILD_ENV, &dir)
+        .env("CLICOLOR_FORCE", "1")
+        .output()
+        .unwrap();
+
+    let report = std::fs::read_to_string(dir.join("crash_report.log")).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    (
+        output.status.code(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+        report,
+    )
+}
+
+/// install the panic hook when running as a child, writing crash reports into the directory given by the parent
+fn install_in_child() -> bool {
+    match std::env::var(CHILD_ENV) {
+        Ok(dir) => {
+            let mut config = Config::default();
+            config.set_crash_report_path(PathBuf::from(dir).join("crash_report.log"));
+            config.install_panic_hook();
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+#[test]
+fn panic_is_reported() {
+    if install_in_child() {
+        panic!("settings are corrupt");
+    }
+
+    let (code, stderr, report) = run_child("panic_is_reported", "panic");
+
+    assert_eq!(code, Some(3));
+    assert!(stderr.contains("\u{1b}[31m[FATAL] thread '"), "{}", stderr);
+    assert!(stderr.contains("panicked at tests/panic_hook.rs:"));
+    assert!(stderr.contains("Oh no! The program has crashed"));
+    assert!(report.contains("settings are corrupt"));
+    assert!(report.contains("[PLATFORM INFO]"));
+}
+
+#[test]
+fn unwrap_is_reported() {
+    if install_in_child() {
+        std::env::var_os("HUMANTALK_UNSET_VARIABLE").unwrap();
+    }
+
+    let (code, stderr, report) = run_child("unwrap_is_reported", "unwrap");
+
+    assert_eq!(code, Some(3));
+    assert!(
+        stderr.contains("called `Option::unwrap()` on a `None` value"),
+        "{}",
+        stderr
+    );
+    assert!(report.contains("[FATAL] thread '"));
+    assert!(report.contains("called `Option::unwrap()` on a `None` value"));
+}